@@ -1,11 +1,27 @@
 mod services;
 
+use std::env;
 use std::io::{self};
 
-use services::generate_report::generate_reports;
+use services::commands::{run_command, take_filter};
+use services::config::load_config;
+use services::dedup::review_duplicates;
+use services::filter::ask_filter;
+use services::generate_report::{generate_analysis_reports, generate_reports};
 use services::loader::{DataSet, load_file};
 
 fn main() {
+    let mut config = load_config();
+
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    if let Some(filter) = take_filter(&mut args) {
+        config.filter = Some(filter);
+    }
+    if !args.is_empty() {
+        run_command(&args, &config);
+        return;
+    }
+
     let mut dataset: Option<DataSet> = None;
     loop {
         let mut input = String::new();
@@ -13,7 +29,8 @@ fn main() {
         println!("Select Language Implementation");
         println!("[1] Load the file");
         println!("[2] Generate Reports");
-        println!("[3] Exit");
+        println!("[3] Analysis Reports");
+        println!("[4] Exit");
 
         print!("\nEnter Choice: ");
         use std::io::Write;
@@ -31,17 +48,22 @@ fn main() {
             }
         };
 
-        println!("");
+        println!();
 
         match choice {
-            1 => match load_file() {
-                Ok(data) => {
-                    dataset = Some(data);
+            1 => {
+                config.filter = ask_filter(config.filter.as_deref());
+                match load_file(&config) {
+                    Ok(mut data) => {
+                        review_duplicates(&mut data, &config.dedup);
+                        dataset = Some(data);
+                    }
+                    Err(err) => eprintln!("Error: {}\n", err),
                 }
-                Err(err) => eprintln!("Error: {}\n", err),
-            },
-            2 => generate_reports(&dataset),
-            3 => {
+            }
+            2 => generate_reports(&dataset, &config),
+            3 => generate_analysis_reports(&dataset, &config),
+            4 => {
                 println!("Exiting Program...");
                 break;
             }
@@ -0,0 +1,143 @@
+use crate::services::config::BootstrapConfig;
+use crate::services::loader::DataSet;
+use crate::services::project::Project;
+use crate::services::stats::{quantile, sorted};
+use std::collections::HashMap;
+
+// -----------------------------
+// Random Numbers
+// -----------------------------
+/// SplitMix64: small, fast and reproducible for a given seed.
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform index in `0..n`.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+// -----------------------------
+// Resampling
+// -----------------------------
+/// Draws projects with replacement within each stratum, keeping every stratum's size,
+/// so the groups a report builds are the same as in the original data.
+fn resample<'a>(projects: &'a [Project], strata: &[Vec<usize>], rng: &mut Rng) -> Vec<&'a Project> {
+    strata
+        .iter()
+        .flat_map(|members| {
+            (0..members.len())
+                .map(|_| &projects[members[rng.below(members.len())]])
+                .collect::<Vec<&Project>>()
+        })
+        .collect()
+}
+
+/// 95% percentile intervals of per-group metrics.
+///
+/// `metrics` turns a (resampled) set of projects into `(group, values)` pairs, one value per metric;
+/// the result maps each group to one `(lower, upper)` interval per metric.
+pub fn intervals<S, M>(
+    data: &DataSet,
+    config: &BootstrapConfig,
+    stratum: S,
+    metrics: M,
+) -> HashMap<String, Vec<(f64, f64)>>
+where
+    S: Fn(&Project) -> String,
+    M: Fn(&[&Project]) -> Vec<(String, Vec<f64>)>,
+{
+    let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, project) in data.projects.iter().enumerate() {
+        groups.entry(stratum(project)).or_default().push(i);
+    }
+    let mut strata: Vec<Vec<usize>> = groups.into_values().collect();
+    strata.sort();
+
+    let mut rng = Rng(config.seed);
+    let mut draws: HashMap<String, Vec<Vec<f64>>> = HashMap::new();
+
+    for _ in 0..config.iterations {
+        let sample = resample(&data.projects, &strata, &mut rng);
+        for (group, values) in metrics(&sample) {
+            let entry = draws.entry(group).or_default();
+            entry.resize(values.len(), Vec::new());
+            for (metric, value) in values.into_iter().enumerate() {
+                entry[metric].push(value);
+            }
+        }
+    }
+
+    draws
+        .into_iter()
+        .map(|(group, metrics)| {
+            let bounds = metrics
+                .iter()
+                .map(|values| {
+                    let ordered = sorted(values);
+                    (quantile(&ordered, 0.025), quantile(&ordered, 0.975))
+                })
+                .collect();
+            (group, bounds)
+        })
+        .collect()
+}
+
+/// Whether each interval overlaps the one ranked directly above or below it.
+pub fn overlaps_neighbour(intervals: &[(f64, f64)]) -> Vec<bool> {
+    let overlap = |a: (f64, f64), b: (f64, f64)| a.0 <= b.1 && b.0 <= a.1;
+    (0..intervals.len())
+        .map(|i| {
+            (i > 0 && overlap(intervals[i], intervals[i - 1]))
+                || (i + 1 < intervals.len() && overlap(intervals[i], intervals[i + 1]))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::project::fixtures::project;
+
+    #[test]
+    fn resample_keeps_each_stratum_and_its_size() {
+        let projects: Vec<Project> = ["Region A", "Region A", "Region B", "Region B", "Region B"]
+            .iter()
+            .enumerate()
+            .map(|(row, region)| project(row, region, "100"))
+            .collect();
+        let strata = vec![vec![0, 1], vec![2, 3, 4]];
+
+        let sample = resample(&projects, &strata, &mut Rng(7));
+        let regions: Vec<&str> = sample.iter().map(|p| p.region.as_str()).collect();
+        assert_eq!(
+            regions,
+            ["Region A", "Region A", "Region B", "Region B", "Region B"]
+        );
+
+        // The same seed draws the same projects
+        let rows = |sample: Vec<&Project>| sample.iter().map(|p| p.row_number).collect::<Vec<_>>();
+        assert_eq!(
+            rows(resample(&projects, &strata, &mut Rng(7))),
+            rows(sample)
+        );
+    }
+
+    #[test]
+    fn overlaps_only_adjacent_intervals() {
+        // The first two overlap; the third lies below the second
+        let flags = overlaps_neighbour(&[(9.0, 10.0), (8.0, 9.5), (1.0, 7.5)]);
+        assert_eq!(flags, [true, true, false]);
+        assert_eq!(overlaps_neighbour(&[(1.0, 2.0)]), [false]);
+        // Touching bounds count as overlapping
+        assert_eq!(overlaps_neighbour(&[(2.0, 3.0), (1.0, 2.0)]), [true, true]);
+    }
+}
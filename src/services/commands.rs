@@ -0,0 +1,88 @@
+use crate::services::config::Config;
+use crate::services::generate_report::print_filter;
+use crate::services::loader::{DataSet, load_file};
+use crate::services::reports::database;
+use crate::services::reports::pivot::{self, PivotSpec};
+use crate::services::reports::query;
+use crate::services::reports::report12;
+
+const USAGE: &str = "Usage:
+  csadprg-mco2-data-analysis-pipeline                      interactive menu
+  csadprg-mco2-data-analysis-pipeline contractor <name>    contractor profile
+  csadprg-mco2-data-analysis-pipeline pivot <dimensions> <metrics>...
+                                                           pivot table, e.g. pivot Region,TypeOfWork count 'sum(ContractCost)'
+  csadprg-mco2-data-analysis-pipeline query <sql>          SQL over the projects table
+  csadprg-mco2-data-analysis-pipeline sqlite [file]        export to SQLite (default pipeline_results.sqlite)
+
+Options:
+  --filter <expression>    keep only matching projects, e.g.
+                           --filter 'Region == \"Region X\" && ContractCost > 50e6'";
+
+// -----------------------------
+// Command Line
+// -----------------------------
+/// Removes `--filter <expression>` from `args`, returning the expression.
+pub fn take_filter(args: &mut Vec<String>) -> Option<String> {
+    let idx = args.iter().position(|a| a == "--filter")?;
+    if idx + 1 >= args.len() {
+        args.remove(idx);
+        eprintln!("--filter needs an expression\n");
+        return None;
+    }
+    let filter = args.remove(idx + 1);
+    args.remove(idx);
+    Some(filter)
+}
+
+/// Loads the file for a command and echoes the filter; `None` (after printing why) on failure.
+fn load(config: &Config) -> Option<DataSet> {
+    match load_file(config) {
+        Ok(data) => {
+            print_filter(&data);
+            Some(data)
+        }
+        Err(err) => {
+            eprintln!("Error: {}\n", err);
+            None
+        }
+    }
+}
+
+/// Runs one command given on the command line instead of the menu. The duplicate review is
+/// skipped, since it needs an answer at the prompt.
+pub fn run_command(args: &[String], config: &Config) {
+    let (command, rest) = args.split_first().unwrap();
+
+    match command.as_str() {
+        "contractor" if !rest.is_empty() => {
+            if let Some(data) = load(config) {
+                report12::generate_report(&data, &rest.join(" "));
+            }
+        }
+        // Metrics may come as one comma-separated argument or several
+        "pivot" if rest.len() >= 2 => {
+            let spec = match PivotSpec::parse(&rest[0], &rest[1..].join(",")) {
+                Ok(spec) => spec,
+                Err(err) => {
+                    eprintln!("Error: {err}\n");
+                    return;
+                }
+            };
+            if let Some(data) = load(config) {
+                pivot::generate_report(&data, &spec);
+            }
+        }
+        "query" if !rest.is_empty() => {
+            if let Some(data) = load(config) {
+                query::generate_report(&data, &rest.join(" "));
+            }
+        }
+        "sqlite" if rest.len() <= 1 => {
+            if let Some(data) = load(config) {
+                let path = rest.first().map_or(database::DEFAULT_FILE, String::as_str);
+                database::generate_report(&data, config, path);
+            }
+        }
+        _ => eprintln!("{USAGE}"),
+    }
+}
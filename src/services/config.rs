@@ -0,0 +1,364 @@
+use crate::services::geo::BoundingBox;
+use crate::services::project::{DelayDefinition, IncompletePolicy};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::fs;
+
+const CONFIG_PATH: &str = "pipeline_config.json";
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Config {
+    pub delay_definition: DelayDefinition,
+    pub incomplete_policy: IncompletePolicy,
+    /// "As of" date for ongoing projects; today when not set.
+    pub reference_date: Option<NaiveDate>,
+    /// Keeps only projects with coordinates inside this box when set.
+    pub bbox: Option<BoundingBox>,
+    /// Filter expression applied when loading; `--filter` on the command line overrides it.
+    pub filter: Option<String>,
+    pub red_flags: RedFlagsConfig,
+    pub benford: BenfordConfig,
+    pub dedup: DedupConfig,
+    pub outliers: OutlierConfig,
+    pub forecast: ForecastConfig,
+    pub trends: TrendConfig,
+    pub efficiency: EfficiencyConfig,
+    pub reliability: ReliabilityConfig,
+    pub sensitivity: SensitivityConfig,
+    pub bootstrap: BootstrapConfig,
+    pub contractors: ContractorConfig,
+    pub hotspots: HotspotConfig,
+    pub reference: ReferenceConfig,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RedFlagsConfig {
+    /// Projects of one contractor, place and type of work starting this close together are flagged as split.
+    pub split_window_days: i64,
+    /// Contract costs between `near_ceiling_min_pct` and this percentage below the approved
+    /// budget are flagged as near-ceiling bids.
+    pub near_ceiling_pct: f64,
+    /// Costs closer to the budget than this (including bids exactly at it) are not flagged.
+    pub near_ceiling_min_pct: f64,
+}
+
+impl Default for RedFlagsConfig {
+    fn default() -> Self {
+        RedFlagsConfig {
+            split_window_days: 30,
+            near_ceiling_pct: 1.0,
+            near_ceiling_min_pct: 0.1,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct BenfordConfig {
+    /// Regions and contractors with fewer projects are left out of the breakdowns.
+    pub min_projects: usize,
+}
+
+impl Default for BenfordConfig {
+    fn default() -> Self {
+        BenfordConfig { min_projects: 30 }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct DedupConfig {
+    /// Contract costs this close (as a percentage of the larger) can belong to the same project.
+    pub amount_tolerance_pct: f64,
+    /// Start dates this many days apart can belong to the same project.
+    pub date_tolerance_days: i64,
+    /// Minimum word overlap (0-1) between two descriptions for a fuzzy match.
+    pub min_description_similarity: f64,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        DedupConfig {
+            amount_tolerance_pct: 1.0,
+            date_tolerance_days: 30,
+            min_description_similarity: 0.8,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct OutlierConfig {
+    /// Values whose median/MAD z-score exceeds this in absolute value are outliers.
+    pub z_threshold: f64,
+    /// Values beyond Q1/Q3 by this multiple of the IQR are outliers.
+    pub iqr_multiplier: f64,
+    /// TypeOfWork and Region groups smaller than this are not tested.
+    pub min_group_size: usize,
+}
+
+impl Default for OutlierConfig {
+    fn default() -> Self {
+        OutlierConfig {
+            z_threshold: 3.5,
+            iqr_multiplier: 1.5,
+            min_group_size: 5,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ForecastConfig {
+    /// Weight (0-1) of the latest year in simple exponential smoothing.
+    pub smoothing_alpha: f64,
+}
+
+impl Default for ForecastConfig {
+    fn default() -> Self {
+        ForecastConfig {
+            smoothing_alpha: 0.5,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct TrendConfig {
+    /// Year that report3's "change vs base year" columns compare with; the earliest year in the data when not set.
+    pub base_year: Option<i32>,
+}
+
+/// Formula behind report1's `efficiency_score`, chosen by `efficiency.formula` in the config.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EfficiencyFormula {
+    /// Median savings divided by average delay, scaled so the best region scores 100.
+    #[default]
+    Ratio,
+    /// Weighted mean of the z-scores of savings %, on-time rate and (negated) average delay.
+    Zscore,
+    /// Weighted mean of the percentile ranks (0-100) of the same three components.
+    Rank,
+}
+
+impl EfficiencyFormula {
+    pub fn name(self) -> &'static str {
+        match self {
+            EfficiencyFormula::Ratio => "ratio (median savings / avg delay, best region = 100)",
+            EfficiencyFormula::Zscore => "weighted z-score composite",
+            EfficiencyFormula::Rank => "weighted percentile-rank composite",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct EfficiencyConfig {
+    pub formula: EfficiencyFormula,
+    /// Weights of the composite formulas; ignored by `ratio`.
+    pub savings_weight: f64,
+    pub on_time_weight: f64,
+    pub delay_weight: f64,
+}
+
+impl Default for EfficiencyConfig {
+    fn default() -> Self {
+        EfficiencyConfig {
+            formula: EfficiencyFormula::Ratio,
+            savings_weight: 1.0,
+            on_time_weight: 1.0,
+            delay_weight: 1.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ReliabilityConfig {
+    /// Average delay (days) at which report2's reliability index drops to zero.
+    pub delay_denominator_days: f64,
+    /// Contractors whose reliability index is below this are flagged High Risk.
+    pub high_risk_cutoff: f64,
+}
+
+impl Default for ReliabilityConfig {
+    fn default() -> Self {
+        ReliabilityConfig {
+            delay_denominator_days: 90.0,
+            high_risk_cutoff: 50.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SensitivityConfig {
+    pub delay_denominators: Vec<f64>,
+    pub high_risk_cutoffs: Vec<f64>,
+    /// `[savings, on_time, delay]` weights tried with each composite efficiency formula.
+    pub weight_sets: Vec<[f64; 3]>,
+}
+
+impl Default for SensitivityConfig {
+    fn default() -> Self {
+        SensitivityConfig {
+            delay_denominators: vec![30.0, 60.0, 90.0, 120.0, 180.0],
+            high_risk_cutoffs: vec![40.0, 50.0, 60.0],
+            weight_sets: vec![
+                [1.0, 1.0, 1.0],
+                [2.0, 1.0, 1.0],
+                [1.0, 2.0, 1.0],
+                [1.0, 1.0, 2.0],
+            ],
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct BootstrapConfig {
+    /// Adds 95% bootstrap intervals to Reports 1-3 when set.
+    pub enabled: bool,
+    pub iterations: usize,
+    /// The same seed always gives the same intervals.
+    pub seed: u64,
+}
+
+impl Default for BootstrapConfig {
+    fn default() -> Self {
+        BootstrapConfig {
+            enabled: false,
+            iterations: 1000,
+            seed: 42,
+        }
+    }
+}
+
+/// How a joint venture's contract cost and savings are credited to its member firms.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JvAttribution {
+    /// Split evenly between the members.
+    #[default]
+    Equal,
+    /// Every member is credited with the whole amount.
+    Full,
+    /// The first-named member is credited with the whole amount.
+    Lead,
+}
+
+impl JvAttribution {
+    pub fn name(self) -> &'static str {
+        match self {
+            JvAttribution::Equal => "split equally",
+            JvAttribution::Full => "in full to every member",
+            JvAttribution::Lead => "in full to the lead member",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ContractorConfig {
+    /// CSV of `alias,canonical` contractor names; ignored when the file does not exist.
+    pub alias_file: String,
+    pub split_joint_ventures: bool,
+    pub jv_attribution: JvAttribution,
+}
+
+impl Default for ContractorConfig {
+    fn default() -> Self {
+        ContractorConfig {
+            alias_file: "contractor_aliases.csv".to_string(),
+            split_joint_ventures: true,
+            jv_attribution: JvAttribution::Equal,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct HotspotConfig {
+    /// Side of the square grid cells.
+    pub grid_cell_km: f64,
+    /// DBSCAN neighbourhood radius.
+    pub radius_km: f64,
+    /// Projects needed within the radius (DBSCAN) or in a cell (grid) to form a hotspot.
+    pub min_projects: usize,
+}
+
+impl Default for HotspotConfig {
+    fn default() -> Self {
+        HotspotConfig {
+            grid_cell_km: 5.0,
+            radius_km: 2.0,
+            min_projects: 5,
+        }
+    }
+}
+
+/// Population, land area and flood-hazard tables; each is skipped when its file does not exist.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ReferenceConfig {
+    /// Keyed by a `Region` column.
+    pub region_file: String,
+    /// Keyed by a `Province` column.
+    pub province_file: String,
+    /// Keyed by `Province` and `LegislativeDistrict` columns.
+    pub district_file: String,
+}
+
+impl Default for ReferenceConfig {
+    fn default() -> Self {
+        ReferenceConfig {
+            region_file: "reference/regions.csv".to_string(),
+            province_file: "reference/provinces.csv".to_string(),
+            district_file: "reference/districts.csv".to_string(),
+        }
+    }
+}
+
+// -----------------------------
+// Load Config
+// -----------------------------
+/// Settings that parse but would break a report, such as a zero delay denominator (0/0).
+fn validate(config: &Config) -> Result<(), String> {
+    if config.reliability.delay_denominator_days <= 0.0 {
+        return Err("reliability.delay_denominator_days must be greater than 0".to_string());
+    }
+    if config
+        .sensitivity
+        .delay_denominators
+        .iter()
+        .any(|d| *d <= 0.0)
+    {
+        return Err("sensitivity.delay_denominators must all be greater than 0".to_string());
+    }
+    Ok(())
+}
+
+/// Reads `pipeline_config.json` if present; missing keys fall back to their defaults.
+pub fn load_config() -> Config {
+    let Ok(contents) = fs::read_to_string(CONFIG_PATH) else {
+        return Config::default();
+    };
+
+    let parsed = serde_json::from_str::<Config>(&contents).map_err(|err| err.to_string());
+    match parsed.and_then(|config| validate(&config).map(|_| config)) {
+        Ok(config) => {
+            println!("Loaded settings from {CONFIG_PATH}\n");
+            config
+        }
+        Err(err) => {
+            eprintln!("Error: invalid {CONFIG_PATH} ({err}), using defaults\n");
+            Config::default()
+        }
+    }
+}
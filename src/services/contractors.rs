@@ -0,0 +1,170 @@
+use crate::services::config::{ContractorConfig, JvAttribution};
+use crate::services::project::{ContractorShare, Project};
+use csv::Reader;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::path::Path;
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+/// One line of the alias file: every name normalising to `alias` is read as `canonical`.
+#[derive(Deserialize, Debug)]
+struct AliasRow {
+    alias: String,
+    canonical: String,
+}
+
+// Dropped from the end of a name, so "ABC Construction Inc." and "ABC CONSTRUCTION" match
+const SUFFIXES: [&str; 10] = [
+    "INC",
+    "INCORPORATED",
+    "CORP",
+    "CORPORATION",
+    "CO",
+    "COMPANY",
+    "LTD",
+    "LIMITED",
+    "OPC",
+    "JV",
+];
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+/// Uppercase, "&" read as "AND", punctuation dropped and trailing corporate suffixes removed.
+pub fn normalise_name(name: &str) -> String {
+    let cleaned: String = name
+        .to_uppercase()
+        .replace('&', " AND ")
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+
+    let mut words: Vec<&str> = cleaned.split_whitespace().collect();
+    while words.len() > 1 && SUFFIXES.contains(words.last().unwrap()) {
+        words.pop();
+    }
+    words.join(" ")
+}
+
+/// Member firms of a joint venture written as "A / B (JV)"; a single name otherwise.
+fn member_names(raw: &str) -> Vec<&str> {
+    raw.split('/')
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .collect()
+}
+
+fn load_aliases(path: &str) -> Result<HashMap<String, String>, Box<dyn Error>> {
+    let mut aliases = HashMap::new();
+    if !Path::new(path).exists() {
+        return Ok(aliases);
+    }
+
+    let mut reader = Reader::from_path(path)?;
+    for row in reader.deserialize() {
+        let row: AliasRow = row?;
+        // Normalised like any other name, so the canonical firm merges with its unaliased rows
+        aliases.insert(normalise_name(&row.alias), normalise_name(&row.canonical));
+    }
+    Ok(aliases)
+}
+
+/// Every member is kept; `lead` only moves the money, giving the other members a share of 0.
+fn shares(members: &[String], rule: JvAttribution) -> Vec<ContractorShare> {
+    let n = members.len() as f64;
+    members
+        .iter()
+        .enumerate()
+        .map(|(i, name)| ContractorShare {
+            name: name.clone(),
+            share: match rule {
+                JvAttribution::Equal => 1.0 / n,
+                JvAttribution::Full => 1.0,
+                JvAttribution::Lead if i == 0 => 1.0,
+                JvAttribution::Lead => 0.0,
+            },
+        })
+        .collect()
+}
+
+// -----------------------------
+// Resolve Contractors
+// -----------------------------
+/// Rewrites each project's contractor to its normalised (or aliased) name and, for joint
+/// ventures, credits the member firms according to `config.jv_attribution`.
+pub fn resolve_contractors(projects: &mut [Project], config: &ContractorConfig) {
+    let aliases = match load_aliases(&config.alias_file) {
+        Ok(aliases) => aliases,
+        Err(err) => {
+            eprintln!(
+                "Error: could not read {} ({err}), continuing without aliases",
+                config.alias_file
+            );
+            HashMap::new()
+        }
+    };
+    let resolve = |name: &str| {
+        let key = normalise_name(name);
+        aliases.get(&key).cloned().unwrap_or(key)
+    };
+
+    let mut joint_ventures = 0;
+
+    for project in projects.iter_mut() {
+        let raw = project.raw_contractor.as_str();
+
+        // An alias for the whole string wins over splitting it
+        let mut members: Vec<String> = match aliases.get(&normalise_name(raw)) {
+            Some(canonical) => vec![canonical.clone()],
+            None => member_names(raw).into_iter().map(resolve).collect(),
+        };
+        let mut seen = HashSet::new();
+        members.retain(|m| seen.insert(m.clone()));
+        if members.is_empty() {
+            members.push(raw.to_string());
+        }
+
+        project.contractor = if members.len() > 1 {
+            joint_ventures += 1;
+            format!("{} (JV)", members.join(" / "))
+        } else {
+            members[0].clone()
+        };
+
+        project.members = if config.split_joint_ventures {
+            shares(&members, config.jv_attribution)
+        } else {
+            shares(
+                std::slice::from_ref(&project.contractor),
+                JvAttribution::Equal,
+            )
+        };
+        project.firms = members;
+    }
+
+    let count = |names: Vec<&str>| names.into_iter().collect::<HashSet<_>>().len();
+    let raw_names = count(projects.iter().map(|p| p.raw_contractor.as_str()).collect());
+    let entities = count(projects.iter().map(|p| p.contractor.as_str()).collect());
+    let firms = count(
+        projects
+            .iter()
+            .flat_map(|p| p.members.iter().map(|m| m.name.as_str()))
+            .collect(),
+    );
+
+    print!(
+        "Contractors: {raw_names} names as written, {entities} after normalisation ({} aliases)",
+        aliases.len()
+    );
+    if config.split_joint_ventures {
+        println!(
+            ", {firms} firms after splitting {joint_ventures} joint-venture projects (costs {})\n",
+            config.jv_attribution.name()
+        );
+    } else {
+        println!("\n");
+    }
+}
@@ -0,0 +1,247 @@
+use crate::services::config::DedupConfig;
+use crate::services::export::export_csv;
+use crate::services::generate_report::ask_yes_no;
+use crate::services::loader::DataSet;
+use crate::services::project::Project;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+
+const REVIEW_PATH: &str = "duplicates_review.csv";
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateReviewRow {
+    pub group_id: String,
+    pub match_type: String,
+    pub row_number: usize,
+    pub project_id: String,
+    pub contract_id: String,
+    pub contractor: String,
+    pub location: String,
+    pub funding_year: String,
+    pub contract_cost: f64,
+    pub start_date: String,
+    pub similarity: f64,
+    pub description: String,
+    pub exclude: String,
+}
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    parent[i] = root;
+    root
+}
+
+fn union(parent: &mut [usize], a: usize, b: usize) {
+    let (ra, rb) = (find(parent, a), find(parent, b));
+    if ra != rb {
+        parent[ra.max(rb)] = ra.min(rb);
+    }
+}
+
+fn tokens(text: &str) -> HashSet<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Jaccard overlap of the words in two descriptions.
+fn similarity(a: &str, b: &str) -> f64 {
+    let (ta, tb) = (tokens(a), tokens(b));
+    let union = ta.union(&tb).count();
+    if union == 0 {
+        return 0.0;
+    }
+    ta.intersection(&tb).count() as f64 / union as f64
+}
+
+fn is_fuzzy_match(a: &Project, b: &Project, config: &DedupConfig) -> bool {
+    if a.municipality != b.municipality {
+        return false;
+    }
+
+    let larger = a.contract_cost.abs().max(b.contract_cost.abs());
+    let amount_close = larger == 0.0
+        || (a.contract_cost - b.contract_cost).abs() / larger * 100.0
+            <= config.amount_tolerance_pct;
+
+    let dates_close = match (a.start_date, b.start_date) {
+        (Some(x), Some(y)) => (x - y).num_days().abs() <= config.date_tolerance_days,
+        _ => false,
+    };
+
+    amount_close
+        && dates_close
+        && similarity(&a.description, &b.description) >= config.min_description_similarity
+}
+
+// -----------------------------
+// Duplicate Detection
+// -----------------------------
+/// Groups exact duplicates (same contract ID) and fuzzy duplicates (same contractor and
+/// location with close amounts, dates and descriptions). Each row is labelled by how it
+/// matched: rows repeating an earlier contract ID in their group are Exact and pre-marked
+/// for exclusion, the rest are kept for review.
+pub fn find_duplicates(projects: &[Project], config: &DedupConfig) -> Vec<DuplicateReviewRow> {
+    let mut parent: Vec<usize> = (0..projects.len()).collect();
+
+    let mut by_contract: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut by_place: HashMap<(&str, &str), Vec<usize>> = HashMap::new();
+    for (i, project) in projects.iter().enumerate() {
+        if !project.contract_id.is_empty() {
+            by_contract.entry(&project.contract_id).or_default().push(i);
+        }
+        by_place
+            .entry((&project.contractor, &project.province))
+            .or_default()
+            .push(i);
+    }
+
+    for members in by_contract.values() {
+        for &i in &members[1..] {
+            union(&mut parent, members[0], i);
+        }
+    }
+
+    let mut fuzzy_pairs = Vec::new();
+    for members in by_place.values() {
+        for (n, &i) in members.iter().enumerate() {
+            for &j in &members[n + 1..] {
+                if find(&mut parent, i) != find(&mut parent, j)
+                    && is_fuzzy_match(&projects[i], &projects[j], config)
+                {
+                    fuzzy_pairs.push((i, j));
+                }
+            }
+        }
+    }
+    for (i, j) in fuzzy_pairs {
+        union(&mut parent, i, j);
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..projects.len() {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().push(i);
+    }
+
+    let mut groups: Vec<(usize, Vec<usize>)> =
+        groups.into_iter().filter(|(_, m)| m.len() > 1).collect();
+    groups.sort_by_key(|(root, _)| projects[*root].row_number);
+
+    let mut rows = Vec::new();
+    for (n, (_, members)) in groups.into_iter().enumerate() {
+        let kept = &projects[members[0]];
+        let mut id_counts: HashMap<&str, usize> = HashMap::new();
+        for &i in &members {
+            *id_counts.entry(&projects[i].contract_id).or_default() += 1;
+        }
+        let mut seen_ids: HashSet<&str> = HashSet::new();
+
+        for &i in &members {
+            let project = &projects[i];
+            let id = project.contract_id.as_str();
+            let exact = !id.is_empty() && id_counts[id] > 1;
+            let match_type = if exact { "Exact" } else { "Fuzzy" };
+            // The first row with each contract ID is kept
+            let exclude = if exact && !seen_ids.insert(id) {
+                "Y"
+            } else {
+                "N"
+            };
+
+            rows.push(DuplicateReviewRow {
+                group_id: format!("DUP-{:04}", n + 1),
+                match_type: match_type.to_string(),
+                row_number: project.row_number,
+                project_id: project.project_id.clone(),
+                contract_id: project.contract_id.clone(),
+                contractor: project.contractor.clone(),
+                location: project.location(),
+                funding_year: project.funding_year.clone(),
+                contract_cost: round2(project.contract_cost),
+                start_date: project
+                    .start_date
+                    .map(|d| d.to_string())
+                    .unwrap_or_default(),
+                similarity: round2(similarity(&kept.description, &project.description)),
+                description: project.description.clone(),
+                exclude: exclude.to_string(),
+            });
+        }
+    }
+    rows
+}
+
+fn read_exclusions() -> Result<HashSet<usize>, Box<dyn Error>> {
+    let mut reader = csv::Reader::from_path(REVIEW_PATH)?;
+    let mut excluded = HashSet::new();
+    for result in reader.deserialize() {
+        let row: DuplicateReviewRow = result?;
+        if row.exclude.trim().eq_ignore_ascii_case("Y") {
+            excluded.insert(row.row_number);
+        }
+    }
+    Ok(excluded)
+}
+
+/// Writes the duplicate review file and, if confirmed, drops the rows marked `exclude = Y`
+/// in it (after any manual edits) from the dataset.
+pub fn review_duplicates(data: &mut DataSet, config: &DedupConfig) {
+    let rows = find_duplicates(&data.projects, config);
+    if rows.is_empty() {
+        println!("No duplicate projects found.\n");
+        return;
+    }
+
+    let groups: HashSet<&str> = rows.iter().map(|r| r.group_id.as_str()).collect();
+    let marked = rows.iter().filter(|r| r.exclude == "Y").count();
+
+    export_csv(REVIEW_PATH, &rows, data);
+
+    println!(
+        "Found {} possible duplicate groups ({} rows marked for exclusion), review file exported to {}",
+        groups.len(),
+        marked,
+        REVIEW_PATH
+    );
+
+    if !ask_yes_no("Exclude rows marked exclude=Y in the review file before reports") {
+        println!();
+        return;
+    }
+
+    let excluded = match read_exclusions() {
+        Ok(excluded) => excluded,
+        Err(err) => {
+            eprintln!("Error: could not read {REVIEW_PATH} ({err})\n");
+            return;
+        }
+    };
+
+    let before = data.projects.len();
+    data.projects.retain(|p| !excluded.contains(&p.row_number));
+
+    let removed = before - data.projects.len();
+    data.filtered_rows -= removed as i32;
+
+    println!(
+        "Excluded {} duplicate rows, {} rows remain\n",
+        removed, data.filtered_rows
+    );
+}
@@ -0,0 +1,100 @@
+use crate::services::loader::DataSet;
+use chrono::Local;
+use serde::Serialize;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::Write;
+
+// -----------------------------
+// Data Structure
+// -----------------------------
+/// Written next to every export as `<file>.meta.json` so a table can be traced back to how it was built.
+#[derive(Serialize)]
+struct ExportMetadata<'a> {
+    file: &'a str,
+    generated_at: String,
+    source_file: &'a str,
+    source_hash: &'a str,
+    year_filter: &'a str,
+    source_rows: i32,
+    projects: usize,
+    rows: usize,
+    delay_definition: &'a str,
+    incomplete_policy: &'a str,
+    reference_date: String,
+    bbox: Option<String>,
+    filter: Option<&'a str>,
+    reference_tables: Vec<String>,
+}
+
+pub fn write_metadata(path: &str, rows: usize, data: &DataSet) {
+    let metadata = ExportMetadata {
+        file: path,
+        generated_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        source_file: &data.source_file,
+        source_hash: &data.source_hash,
+        year_filter: &data.year_filter,
+        source_rows: data.total_rows,
+        projects: data.projects.len(),
+        rows,
+        delay_definition: data.delay.definition.name(),
+        incomplete_policy: data.delay.policy.name(),
+        reference_date: data.delay.reference_date.to_string(),
+        bbox: data.bbox.map(|b| b.describe()),
+        filter: data.filter.as_deref(),
+        reference_tables: data.reference.describe(),
+    };
+
+    let json_str = serde_json::to_string_pretty(&metadata).unwrap();
+    let mut file = File::create(format!("{path}.meta.json")).unwrap();
+    file.write_all(json_str.as_bytes()).unwrap();
+}
+
+// -----------------------------
+// Formatting
+// -----------------------------
+/// Console display for optional columns: blank when there is no value.
+pub fn display_option<T: Display>(value: &Option<T>) -> String {
+    value.as_ref().map(|v| v.to_string()).unwrap_or_default()
+}
+
+// -----------------------------
+// Writers
+// -----------------------------
+pub fn export_csv<T: Serialize>(path: &str, rows: &[T], data: &DataSet) {
+    let mut wtr = csv::Writer::from_path(path).unwrap();
+    for row in rows {
+        wtr.serialize(row).unwrap();
+    }
+    wtr.flush().unwrap();
+
+    write_metadata(path, rows.len(), data);
+}
+
+pub fn export_json<T: Serialize>(path: &str, value: &T, data: &DataSet) {
+    let json_str = serde_json::to_string_pretty(value).unwrap();
+    let mut file = File::create(path).unwrap();
+    file.write_all(json_str.as_bytes()).unwrap();
+
+    write_metadata(path, 1, data);
+}
+
+/// For formats built by hand (GraphML, DOT); `rows` is what the metadata should report.
+pub fn export_text(path: &str, contents: &str, rows: usize, data: &DataSet) {
+    let mut file = File::create(path).unwrap();
+    file.write_all(contents.as_bytes()).unwrap();
+
+    write_metadata(path, rows, data);
+}
+
+/// For tables whose columns are only known at run time (pivots, queries).
+pub fn export_records(path: &str, headers: &[String], rows: &[Vec<String>], data: &DataSet) {
+    let mut wtr = csv::Writer::from_path(path).unwrap();
+    wtr.write_record(headers).unwrap();
+    for row in rows {
+        wtr.write_record(row).unwrap();
+    }
+    wtr.flush().unwrap();
+
+    write_metadata(path, rows.len(), data);
+}
@@ -0,0 +1,155 @@
+use crate::services::project::{DelayOptions, Project};
+use chrono::NaiveDate;
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+/// A project field that filters and pivots can refer to by name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    MainIsland,
+    Region,
+    Province,
+    Municipality,
+    LegislativeDistrict,
+    ProjectId,
+    ContractId,
+    Description,
+    Contractor,
+    TypeOfWork,
+    Status,
+    StartMonth,
+    FundingYear,
+    ApprovedBudget,
+    ContractCost,
+    Savings,
+    Delay,
+    StartDate,
+    ActualCompletionDate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Text,
+    Number,
+    Date,
+}
+
+// Names accepted in expressions; the source column names where there is one
+const FIELDS: [(&str, Field); 19] = [
+    ("MainIsland", Field::MainIsland),
+    ("Region", Field::Region),
+    ("Province", Field::Province),
+    ("Municipality", Field::Municipality),
+    ("LegislativeDistrict", Field::LegislativeDistrict),
+    ("ProjectId", Field::ProjectId),
+    ("ContractId", Field::ContractId),
+    ("ProjectDescription", Field::Description),
+    ("Contractor", Field::Contractor),
+    ("TypeOfWork", Field::TypeOfWork),
+    ("Status", Field::Status),
+    ("StartMonth", Field::StartMonth),
+    ("FundingYear", Field::FundingYear),
+    ("ApprovedBudgetForContract", Field::ApprovedBudget),
+    ("ContractCost", Field::ContractCost),
+    ("Savings", Field::Savings),
+    ("Delay", Field::Delay),
+    ("StartDate", Field::StartDate),
+    ("ActualCompletionDate", Field::ActualCompletionDate),
+];
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+/// Lowercase with spaces and underscores dropped, so "type_of_work" finds TypeOfWork.
+fn squash(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace() && *c != '_')
+        .collect::<String>()
+        .to_lowercase()
+}
+
+impl Field {
+    pub fn lookup(name: &str) -> Option<Field> {
+        let name = squash(name);
+        FIELDS
+            .iter()
+            .find(|(n, _)| squash(n) == name)
+            .map(|(_, f)| *f)
+    }
+
+    pub fn names() -> Vec<&'static str> {
+        FIELDS.iter().map(|(n, _)| *n).collect()
+    }
+
+    pub fn name(self) -> &'static str {
+        FIELDS.iter().find(|(_, f)| *f == self).unwrap().0
+    }
+
+    pub fn kind(self) -> Kind {
+        match self {
+            Field::FundingYear
+            | Field::ApprovedBudget
+            | Field::ContractCost
+            | Field::Savings
+            | Field::Delay => Kind::Number,
+            Field::StartDate | Field::ActualCompletionDate => Kind::Date,
+            _ => Kind::Text,
+        }
+    }
+
+    pub fn text(self, project: &Project, options: &DelayOptions) -> String {
+        match self {
+            Field::MainIsland => project.main_island.clone(),
+            Field::Region => project.region.clone(),
+            Field::Province => project.province.clone(),
+            Field::Municipality => project.municipality.clone(),
+            Field::LegislativeDistrict => project.legislative_district.clone(),
+            Field::ProjectId => project.project_id.clone(),
+            Field::ContractId => project.contract_id.clone(),
+            Field::Description => project.description.clone(),
+            Field::Contractor => project.contractor.clone(),
+            Field::TypeOfWork => project.type_of_work.clone(),
+            Field::Status => project.status(options.reference_date).name().to_string(),
+            Field::StartMonth => project
+                .start_date
+                .map(|d| d.format("%Y-%m").to_string())
+                .unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+
+    pub fn number(self, project: &Project, options: &DelayOptions) -> Option<f64> {
+        match self {
+            Field::FundingYear => project.funding_year.trim().parse().ok(),
+            Field::ApprovedBudget => Some(project.approved_budget),
+            Field::ContractCost => Some(project.contract_cost),
+            Field::Savings => Some(project.savings()),
+            Field::Delay => project.delay(options).observed().map(|d| d as f64),
+            _ => None,
+        }
+    }
+
+    pub fn date(self, project: &Project) -> Option<NaiveDate> {
+        match self {
+            Field::StartDate => project.start_date,
+            Field::ActualCompletionDate => project.actual_completion_date,
+            _ => None,
+        }
+    }
+
+    /// The value as text whatever its kind, empty when missing; used for group labels.
+    pub fn display(self, project: &Project, options: &DelayOptions) -> String {
+        match self.kind() {
+            Kind::Text => self.text(project, options),
+            Kind::Number => self
+                .number(project, options)
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+            Kind::Date => self
+                .date(project)
+                .map(|d| d.to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,403 @@
+use crate::services::fields::{Field, Kind};
+use crate::services::project::{DelayOptions, Project};
+use chrono::NaiveDate;
+use std::io::{self, Write};
+use std::iter::Peekable;
+use std::str::Chars;
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+/// A parsed filter expression such as
+/// `Region == "Region X" && TypeOfWork ~ "Drainage" && ContractCost > 50e6`.
+#[derive(Debug, Clone)]
+pub struct Filter {
+    pub text: String,
+    expr: Expr,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Field, Op, Literal),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Contains,
+    NotContains,
+}
+
+#[derive(Debug, Clone)]
+enum Literal {
+    Text(String),
+    Number(f64),
+    Date(NaiveDate),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Text(String),
+    Number(f64),
+    Op(Op),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+// -----------------------------
+// Tokenizer
+// -----------------------------
+fn read_while<F>(chars: &mut Peekable<Chars>, first: char, keep: F) -> String
+where
+    F: Fn(char, char) -> bool,
+{
+    let mut out = String::from(first);
+    while let Some(&c) = chars.peek() {
+        if !keep(*out.as_bytes().last().unwrap() as char, c) {
+            break;
+        }
+        out.push(c);
+        chars.next();
+    }
+    out
+}
+
+fn next_is(chars: &mut Peekable<Chars>, expected: char) -> bool {
+    chars.next_if_eq(&expected).is_some()
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '~' => Token::Op(Op::Contains),
+            '&' if next_is(&mut chars, '&') => Token::And,
+            '|' if next_is(&mut chars, '|') => Token::Or,
+            '=' if next_is(&mut chars, '=') => Token::Op(Op::Eq),
+            '!' if next_is(&mut chars, '=') => Token::Op(Op::Ne),
+            '!' if next_is(&mut chars, '~') => Token::Op(Op::NotContains),
+            '!' => Token::Not,
+            '>' if next_is(&mut chars, '=') => Token::Op(Op::Ge),
+            '>' => Token::Op(Op::Gt),
+            '<' if next_is(&mut chars, '=') => Token::Op(Op::Le),
+            '<' => Token::Op(Op::Lt),
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => value.extend(chars.next()),
+                        Some(c) => value.push(c),
+                        None => return Err("unterminated string".to_string()),
+                    }
+                }
+                Token::Text(value)
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '.' => {
+                // Exponents may carry a sign, as in 1.5e-3
+                let raw = read_while(&mut chars, c, |prev, c| {
+                    c.is_ascii_alphanumeric()
+                        || c == '.'
+                        || c == '_'
+                        || ((c == '-' || c == '+') && (prev == 'e' || prev == 'E'))
+                });
+                let number = raw
+                    .replace('_', "")
+                    .parse()
+                    .map_err(|_| format!("\"{raw}\" is not a number"))?;
+                Token::Number(number)
+            }
+            c if c.is_alphabetic() => Token::Ident(read_while(&mut chars, c, |_, c| {
+                c.is_alphanumeric() || c == '_'
+            })),
+            c => return Err(format!("unexpected \"{c}\"")),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+// -----------------------------
+// Parser
+// -----------------------------
+// expr       := and ("||" and)*
+// and        := unary ("&&" unary)*
+// unary      := "!" unary | "(" expr ")" | comparison
+// comparison := field op value
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            left = Expr::And(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Not) => Ok(Expr::Not(Box::new(self.unary()?))),
+            Some(Token::Open) => {
+                let inner = self.expr()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(inner),
+                    _ => Err("missing \")\"".to_string()),
+                }
+            }
+            Some(Token::Ident(name)) => self.comparison(&name),
+            Some(token) => Err(format!("expected a field name, found {token:?}")),
+            None => Err("expression ends too early".to_string()),
+        }
+    }
+
+    fn comparison(&mut self, name: &str) -> Result<Expr, String> {
+        let field = Field::lookup(name).ok_or_else(|| {
+            format!(
+                "unknown field {name} (known: {})",
+                Field::names().join(", ")
+            )
+        })?;
+
+        let Some(Token::Op(op)) = self.next() else {
+            return Err(format!("expected an operator after {name}"));
+        };
+
+        let kind = field.kind();
+        if matches!(op, Op::Contains | Op::NotContains) && kind != Kind::Text {
+            return Err(format!("~ and !~ only apply to text fields, not {name}"));
+        }
+        if !matches!(op, Op::Eq | Op::Ne | Op::Contains | Op::NotContains) && kind == Kind::Text {
+            return Err(format!(
+                "{name} is text and can only be compared with ==, !=, ~ or !~"
+            ));
+        }
+
+        let literal = match (kind, self.next()) {
+            (Kind::Text, Some(Token::Text(value))) => Literal::Text(value.to_lowercase()),
+            (Kind::Text, Some(Token::Number(value))) => Literal::Text(value.to_string()),
+            (Kind::Number, Some(Token::Number(value))) => Literal::Number(value),
+            (Kind::Date, Some(Token::Text(value))) => Literal::Date(
+                NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                    .map_err(|_| format!("{name} needs a \"YYYY-MM-DD\" date, not \"{value}\""))?,
+            ),
+            (Kind::Number, _) => return Err(format!("{name} needs a number")),
+            (Kind::Date, _) => return Err(format!("{name} needs a \"YYYY-MM-DD\" date")),
+            (Kind::Text, _) => return Err(format!("{name} needs a quoted value")),
+        };
+        Ok(Expr::Compare(field, op, literal))
+    }
+}
+
+// -----------------------------
+// Evaluation
+// -----------------------------
+fn compare<T: PartialOrd>(value: Option<T>, op: Op, literal: T) -> bool {
+    let Some(value) = value else {
+        return false;
+    };
+    match op {
+        Op::Eq => value == literal,
+        Op::Ne => value != literal,
+        Op::Gt => value > literal,
+        Op::Ge => value >= literal,
+        Op::Lt => value < literal,
+        Op::Le => value <= literal,
+        Op::Contains | Op::NotContains => false,
+    }
+}
+
+impl Expr {
+    fn matches(&self, project: &Project, options: &DelayOptions) -> bool {
+        match self {
+            Expr::And(a, b) => a.matches(project, options) && b.matches(project, options),
+            Expr::Or(a, b) => a.matches(project, options) || b.matches(project, options),
+            Expr::Not(inner) => !inner.matches(project, options),
+            Expr::Compare(field, op, Literal::Text(literal)) => {
+                let value = field.text(project, options).to_lowercase();
+                match op {
+                    Op::Contains => value.contains(literal),
+                    Op::NotContains => !value.contains(literal),
+                    _ => compare(Some(value.as_str()), *op, literal.as_str()),
+                }
+            }
+            Expr::Compare(field, op, Literal::Number(literal)) => {
+                compare(field.number(project, options), *op, *literal)
+            }
+            Expr::Compare(field, op, Literal::Date(literal)) => {
+                compare(field.date(project), *op, *literal)
+            }
+        }
+    }
+}
+
+impl Filter {
+    pub fn parse(text: &str) -> Result<Filter, String> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            pos: 0,
+        };
+        let expr = parser.expr()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("unexpected {token:?} after the expression"));
+        }
+        Ok(Filter {
+            text: text.trim().to_string(),
+            expr,
+        })
+    }
+
+    pub fn matches(&self, project: &Project, options: &DelayOptions) -> bool {
+        self.expr.matches(project, options)
+    }
+}
+
+// -----------------------------
+// Prompt
+// -----------------------------
+/// Asks for a filter before loading; Enter keeps `current`, "-" clears it.
+pub fn ask_filter(current: Option<&str>) -> Option<String> {
+    loop {
+        match current {
+            Some(filter) => print!("Filter expression (Enter to keep {filter}, - for none): "),
+            None => print!("Filter expression (Enter for none): "),
+        }
+        io::stdout().flush().unwrap();
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        println!();
+
+        match input.trim() {
+            "" => return current.map(str::to_string),
+            "-" => return None,
+            text => match Filter::parse(text) {
+                Ok(_) => return Some(text.to_string()),
+                Err(err) => println!("Invalid filter: {err}\n"),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::project::fixtures::project;
+
+    fn matches(text: &str, project: &Project) -> bool {
+        let options = DelayOptions {
+            definition: Default::default(),
+            policy: Default::default(),
+            reference_date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        };
+        Filter::parse(text).unwrap().matches(project, &options)
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let p = project(1, "Region A", "50");
+        // Region A || (Region B && cost > 100)
+        assert!(matches(
+            r#"Region == "Region A" || Region == "Region B" && ContractCost > 100"#,
+            &p
+        ));
+        assert!(matches(
+            r#"ContractCost > 100 && Region == "Region B" || Region == "Region A""#,
+            &p
+        ));
+    }
+
+    #[test]
+    fn parentheses_override_precedence() {
+        let p = project(1, "Region A", "50");
+        assert!(!matches(
+            r#"(Region == "Region A" || Region == "Region B") && ContractCost > 100"#,
+            &p
+        ));
+    }
+
+    #[test]
+    fn not_negates_the_next_term() {
+        let p = project(1, "Region A", "50");
+        assert!(!matches(r#"!Region == "Region A""#, &p));
+        assert!(matches(r#"!(Region == "Region B")"#, &p));
+        assert!(matches(
+            r#"!Region == "Region B" && ContractCost < 100"#,
+            &p
+        ));
+        assert!(!matches(
+            r#"!(Region == "Region B" || ContractCost < 100)"#,
+            &p
+        ));
+    }
+
+    #[test]
+    fn negative_numbers() {
+        let p = project(1, "Region A", "-20");
+        assert!(matches("ContractCost < -10", &p));
+        assert!(!matches("ContractCost > -10", &p));
+        assert!(matches("ContractCost==-20", &p));
+    }
+
+    #[test]
+    fn exponent_literals() {
+        let p = project(1, "Region A", "60000000");
+        assert!(matches("ContractCost > 50e6", &p));
+        assert!(!matches("ContractCost > 6E7", &p));
+        assert!(matches(
+            "ContractCost >= 6.0e+7 && ContractCost > 1.5e-3",
+            &p
+        ));
+    }
+
+    #[test]
+    fn unterminated_string_is_an_error() {
+        let err = Filter::parse(r#"Region == "Region A"#).unwrap_err();
+        assert_eq!(err, "unterminated string");
+    }
+
+    #[test]
+    fn ordering_a_text_field_is_an_error() {
+        let err = Filter::parse(r#"Region > "Region A""#).unwrap_err();
+        assert!(err.contains("Region is text"), "{err}");
+    }
+}
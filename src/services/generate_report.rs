@@ -1,11 +1,29 @@
+use crate::services::config::Config;
 use crate::services::loader::DataSet;
+use crate::services::reports::database;
+use crate::services::reports::geojson;
+use crate::services::reports::pivot;
+use crate::services::reports::query;
 use crate::services::reports::report1;
 use crate::services::reports::report2;
 use crate::services::reports::report3;
+use crate::services::reports::report4;
+use crate::services::reports::report5;
+use crate::services::reports::report6;
+use crate::services::reports::report7;
+use crate::services::reports::report8;
+use crate::services::reports::report9;
+use crate::services::reports::report10;
+use crate::services::reports::report11;
+use crate::services::reports::report12;
+use crate::services::reports::report13;
+use crate::services::reports::report14;
+use crate::services::reports::report15;
+use crate::services::reports::report16;
 use crate::services::reports::summary;
 use std::io::{self, Write};
 
-fn ask_yes_no(prompt: &str) -> bool {
+pub fn ask_yes_no(prompt: &str) -> bool {
     loop {
         print!("{} (Y/N): ", prompt);
         io::stdout().flush().unwrap();
@@ -22,17 +40,26 @@ fn ask_yes_no(prompt: &str) -> bool {
     }
 }
 
-pub fn generate_reports(data: &Option<DataSet>) {
+/// Echoes the load-time filter above the reports it applies to.
+pub fn print_filter(data: &DataSet) {
+    if let Some(filter) = &data.filter {
+        println!("Filter: {filter} ({} projects)\n", data.projects.len());
+    }
+}
+
+pub fn generate_reports(data: &Option<DataSet>, config: &Config) {
     match data {
         Some(dataset) => loop {
+            print_filter(dataset);
+
             println!("Report 1: Regional Flood Mitigation Efficiency Summary");
-            report1::generate_report(dataset);
+            report1::generate_report(dataset, config);
 
             println!("Report 2: Top Contractors Performance Ranking");
-            report2::generate_report(dataset);
+            report2::generate_report(dataset, config);
 
             println!("Report 3: Annual Project Type Cost Overrun Trends");
-            report3::generate_report(dataset);
+            report3::generate_report(dataset, config);
 
             println!("Summary Stats (summary.json)");
             summary::generate_summary(dataset);
@@ -46,3 +73,62 @@ pub fn generate_reports(data: &Option<DataSet>) {
         }
     }
 }
+
+pub fn generate_analysis_reports(data: &Option<DataSet>, config: &Config) {
+    let Some(dataset) = data else {
+        println!("No dataset loaded. Please load the CSV file first.\n");
+        return;
+    };
+
+    loop {
+        let mut input = String::new();
+
+        print_filter(dataset);
+        println!("Select Analysis Report");
+        println!("[1] Contractor Market Concentration");
+        println!("[2] Suspicious Contract Patterns");
+        println!("[3] Benford's Law Digit Analysis");
+        println!("[4] Statistical Outliers");
+        println!("[5] Delay Distribution");
+        println!("[6] Monthly and Quarterly Time Series");
+        println!("[7] Next-Year Forecast");
+        println!("[8] Ranking Sensitivity");
+        println!("[9] Contractor Profile");
+        println!("[10] Joint Venture Network");
+        println!("[11] GeoJSON Export of Projects");
+        println!("[12] Project Hotspots");
+        println!("[13] Legislative District Breakdown");
+        println!("[14] Spend per Capita and Flood Exposure");
+        println!("[15] Pivot Table");
+        println!("[16] SQL Query");
+        println!("[17] Export SQLite Database");
+        println!("[0] Back");
+
+        print!("\nEnter Choice: ");
+        io::stdout().flush().unwrap();
+        io::stdin().read_line(&mut input).unwrap();
+        println!();
+
+        match input.trim() {
+            "1" => report4::generate_report(dataset),
+            "2" => report5::generate_report(dataset, &config.red_flags),
+            "3" => report6::generate_report(dataset, &config.benford),
+            "4" => report7::generate_report(dataset, config),
+            "5" => report8::generate_report(dataset),
+            "6" => report9::generate_report(dataset),
+            "7" => report10::generate_report(dataset, config),
+            "8" => report11::generate_report(dataset, config),
+            "9" => report12::prompt_report(dataset),
+            "10" => report13::generate_report(dataset),
+            "11" => geojson::generate_geojson(dataset),
+            "12" => report14::generate_report(dataset, &config.hotspots),
+            "13" => report15::generate_report(dataset),
+            "14" => report16::generate_report(dataset),
+            "15" => pivot::prompt_report(dataset),
+            "16" => query::prompt_report(dataset),
+            "17" => database::generate_report(dataset, config, database::DEFAULT_FILE),
+            "0" => break,
+            _ => println!("Invalid choice, try again.\n"),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use serde::Deserialize;
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+#[derive(Debug, Clone, Copy)]
+pub struct Coordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Rectangle in decimal degrees, set as `bbox` in the config to keep only the projects inside it.
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct BoundingBox {
+    pub min_latitude: f64,
+    pub max_latitude: f64,
+    pub min_longitude: f64,
+    pub max_longitude: f64,
+}
+
+// A generous box around the archipelago, to catch swapped or mistyped coordinates
+const PH_LATITUDE: (f64, f64) = (4.0, 22.0);
+const PH_LONGITUDE: (f64, f64) = (116.0, 127.5);
+
+// -----------------------------
+// Validation
+// -----------------------------
+impl Coordinates {
+    /// None for blanks, unparseable values, 0,0 and points outside the Philippines.
+    pub fn parse(latitude: &str, longitude: &str) -> Option<Self> {
+        let latitude = latitude.trim().parse::<f64>().ok()?;
+        let longitude = longitude.trim().parse::<f64>().ok()?;
+
+        let in_ph = (PH_LATITUDE.0..=PH_LATITUDE.1).contains(&latitude)
+            && (PH_LONGITUDE.0..=PH_LONGITUDE.1).contains(&longitude);
+        (in_ph && (latitude, longitude) != (0.0, 0.0)).then_some(Coordinates {
+            latitude,
+            longitude,
+        })
+    }
+}
+
+// Mean Earth radius
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Great-circle distance between two points.
+pub fn haversine_km(a: Coordinates, b: Coordinates) -> f64 {
+    let (lat1, lat2) = (a.latitude.to_radians(), b.latitude.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (b.longitude - a.longitude).to_radians();
+    let h = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * h.sqrt().asin()
+}
+
+impl BoundingBox {
+    pub fn contains(&self, point: Coordinates) -> bool {
+        (self.min_latitude..=self.max_latitude).contains(&point.latitude)
+            && (self.min_longitude..=self.max_longitude).contains(&point.longitude)
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "lat {} to {}, lon {} to {}",
+            self.min_latitude, self.max_latitude, self.min_longitude, self.max_longitude
+        )
+    }
+}
@@ -1,17 +1,38 @@
+use crate::services::config::Config;
+use crate::services::contractors::resolve_contractors;
+use crate::services::filter::Filter;
+use crate::services::geo::BoundingBox;
+use crate::services::project::{Columns, DelayDefinition, DelayOptions, Project};
+use crate::services::reference::{References, fnv1a, load_references};
+use chrono::Local;
 use csv::Reader;
 use std::error::Error;
+use std::fs;
+
+const SOURCE_FILE: &str = "data/dpwh_flood_control_projects.csv";
 
 #[allow(dead_code)]
+#[derive(Clone)]
 pub struct DataSet {
     pub total_rows: i32,
     pub filtered_rows: i32,
-    pub matching_records: Vec<csv::StringRecord>,
     pub headers: csv::StringRecord,
+    pub projects: Vec<Project>,
+    pub source_file: String,
+    /// FNV-1a hash of the source file, to tell extracts with the same name apart.
+    pub source_hash: String,
+    pub year_filter: String,
+    pub delay: DelayOptions,
+    pub bbox: Option<BoundingBox>,
+    /// The filter expression applied at load time, if any.
+    pub filter: Option<String>,
+    pub reference: References,
 }
 
-pub fn load_file() -> Result<DataSet, Box<dyn Error>> {
+pub fn load_file(config: &Config) -> Result<DataSet, Box<dyn Error>> {
     //Read the file
-    let mut reader = Reader::from_path("data/dpwh_flood_control_projects.csv")?;
+    let bytes = fs::read(SOURCE_FILE)?;
+    let mut reader = Reader::from_reader(bytes.as_slice());
 
     let mut total_rows: i32 = 0;
     let mut filtered_rows: i32 = 0;
@@ -22,8 +43,21 @@ pub fn load_file() -> Result<DataSet, Box<dyn Error>> {
         .iter()
         .position(|h| h == "StartDate")
         .expect("Target Column not found");
+    let columns = Columns::from_headers(&headers)?;
+    if config.delay_definition == DelayDefinition::Schedule && !columns.has_target_dates() {
+        return Err("delay_definition is \"schedule\" but the file has no TargetCompletionDate, OriginalCompletionDate, PlannedCompletionDate or ContractDuration column".into());
+    }
+    if !columns.has_description() {
+        println!(
+            "Warning: no ProjectDescription column, so duplicate detection can only match contract IDs"
+        );
+    }
+    let filter = match &config.filter {
+        Some(text) => Some(Filter::parse(text).map_err(|err| format!("Filter: {err}"))?),
+        None => None,
+    };
 
-    let mut matching_records = Vec::new();
+    let mut projects: Vec<Project> = Vec::new();
 
     //Add all rows
     for result in reader.records() {
@@ -31,26 +65,72 @@ pub fn load_file() -> Result<DataSet, Box<dyn Error>> {
         let record = result?;
 
         //Filter rows
-        if let Some(date_str) = record.get(col_index) {
-            let trimmed = date_str.trim();
-            if let Ok(year) = trimmed[0..4].parse::<u32>() {
-                if (2021..=2023).contains(&year) {
-                    filtered_rows += 1;
-                    matching_records.push(record.clone());
-                }
-            }
+        if let Some(date_str) = record.get(col_index)
+            && let Some(Ok(year)) = date_str.trim().get(0..4).map(|y| y.parse::<u32>())
+            && (2021..=2023).contains(&year)
+        {
+            filtered_rows += 1;
+            projects.push(Project::from_record(&columns, &record, total_rows as usize));
         }
     }
 
     println!(
-        "Processing dataset ... ({} rows loaded, {} filtered for {})\n",
-        total_rows, filtered_rows, "2021-2023"
+        "Processing dataset ... ({total_rows} rows loaded, {filtered_rows} filtered for 2021-2023)"
+    );
+
+    let without_coordinates = projects.iter().filter(|p| p.coordinates.is_none()).count();
+    if without_coordinates > 0 {
+        println!(
+            "{without_coordinates} projects have no valid coordinates (missing, 0,0 or outside the Philippines)"
+        );
+    }
+
+    if let Some(bbox) = &config.bbox {
+        projects.retain(|p| p.coordinates.is_some_and(|c| bbox.contains(c)));
+        filtered_rows = projects.len() as i32;
+        println!(
+            "{filtered_rows} projects inside the bounding box ({})",
+            bbox.describe()
+        );
+    }
+    resolve_contractors(&mut projects, &config.contractors);
+
+    let delay = DelayOptions {
+        definition: config.delay_definition,
+        policy: config.incomplete_policy,
+        reference_date: config
+            .reference_date
+            .unwrap_or_else(|| Local::now().date_naive()),
+    };
+    println!(
+        "Delay measured as {}, incomplete projects: {} (as of {})\n",
+        delay.definition.name(),
+        delay.policy.name(),
+        delay.reference_date
     );
 
+    // After name resolution, so Contractor matches the normalised names
+    if let Some(filter) = &filter {
+        projects.retain(|p| filter.matches(p, &delay));
+        filtered_rows = projects.len() as i32;
+        println!(
+            "{filtered_rows} projects match the filter {}\n",
+            filter.text
+        );
+    }
+    let reference = load_references(&projects, &config.reference);
+
     Ok(DataSet {
         total_rows,
         filtered_rows,
-        matching_records,
         headers,
+        projects,
+        source_file: SOURCE_FILE.to_string(),
+        source_hash: fnv1a(&bytes),
+        year_filter: "2021-2023".to_string(),
+        delay,
+        bbox: config.bbox,
+        filter: filter.map(|f| f.text),
+        reference,
     })
 }
@@ -1,3 +1,16 @@
+pub mod bootstrap;
+pub mod commands;
+pub mod config;
+pub mod contractors;
+pub mod dedup;
+pub mod export;
+pub mod fields;
+pub mod filter;
 pub mod generate_report;
+pub mod geo;
 pub mod loader;
+pub mod project;
+pub mod reference;
 pub mod reports;
+pub mod sql;
+pub mod stats;
@@ -0,0 +1,437 @@
+use crate::services::geo::Coordinates;
+use chrono::{Duration, NaiveDate};
+use csv::StringRecord;
+use serde::Deserialize;
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub row_number: usize,
+    pub main_island: String,
+    pub region: String,
+    pub province: String,
+    pub municipality: String,
+    /// Empty when the extract has no LegislativeDistrict column.
+    pub legislative_district: String,
+    pub project_id: String,
+    pub contract_id: String,
+    pub description: String,
+    /// Contractor after name normalisation and aliases; joint ventures keep all members.
+    pub contractor: String,
+    /// `Contractor` as written in the source file.
+    pub raw_contractor: String,
+    /// Firms credited with the project, with their share of its cost and savings.
+    pub members: Vec<ContractorShare>,
+    /// Every firm behind the contractor, one per joint-venture member, however costs are credited.
+    pub firms: Vec<String>,
+    pub type_of_work: String,
+    pub funding_year: String,
+    pub approved_budget: f64,
+    pub contract_cost: f64,
+    pub start_date: Option<NaiveDate>,
+    pub actual_completion_date: Option<NaiveDate>,
+    pub target_completion_date: Option<NaiveDate>,
+    /// ProjectLatitude / ProjectLongitude, when both are present and inside the Philippines.
+    pub coordinates: Option<Coordinates>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ContractorShare {
+    pub name: String,
+    pub share: f64,
+}
+
+/// How delay is measured, chosen by `delay_definition` in the config.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DelayDefinition {
+    /// Days from start to actual completion, i.e. the project duration.
+    #[default]
+    Duration,
+    /// Days from the target completion date to actual completion (negative when early).
+    Schedule,
+}
+
+/// What the reports do with projects that have no actual completion date yet.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IncompletePolicy {
+    /// Leave ongoing projects out of every delay figure.
+    #[default]
+    Exclude,
+    /// Keep ongoing projects as right-censored: their age to date is a lower bound, used only
+    /// where a lower bound is enough (e.g. "delayed over 30 days" shares).
+    Censored,
+    /// Measure ongoing projects up to the reference date as if they finished then.
+    Age,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectStatus {
+    Completed,
+    /// Started on or before the reference date and not yet completed.
+    Ongoing,
+    /// No usable start date, or not started by the reference date.
+    Unknown,
+}
+
+/// Everything needed to turn a project's dates into a delay figure.
+#[derive(Debug, Clone, Copy)]
+pub struct DelayOptions {
+    pub definition: DelayDefinition,
+    pub policy: IncompletePolicy,
+    pub reference_date: NaiveDate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delay {
+    Observed(i64),
+    /// At least this many days; the project is still ongoing.
+    Censored(i64),
+    /// Ongoing and left out under the `exclude` incomplete-project policy.
+    Excluded,
+    /// The dates needed are missing, or the status is unknown.
+    Missing,
+}
+
+/// Column positions of the fields read into a [`Project`].
+pub struct Columns {
+    main_island: usize,
+    region: usize,
+    province: usize,
+    municipality: Option<usize>,
+    legislative_district: Option<usize>,
+    project_id: Option<usize>,
+    contract_id: Option<usize>,
+    description: Option<usize>,
+    contractor: usize,
+    type_of_work: usize,
+    funding_year: usize,
+    approved_budget: usize,
+    contract_cost: usize,
+    start_date: usize,
+    actual_completion_date: usize,
+    target_completion_date: Option<usize>,
+    contract_duration: Option<usize>,
+    latitude: Option<usize>,
+    longitude: Option<usize>,
+}
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+fn parse_f64(v: &str) -> f64 {
+    v.replace(",", "").parse::<f64>().unwrap_or(0.0)
+}
+
+fn parse_date(v: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(v.trim(), "%Y-%m-%d").ok()
+}
+
+fn position(headers: &StringRecord, name: &str) -> Result<usize, String> {
+    headers
+        .iter()
+        .position(|h| h == name)
+        .ok_or_else(|| format!("Column {name} not found"))
+}
+
+/// Position of the first header matching any of `names`, for columns not every extract has.
+fn optional_position(headers: &StringRecord, names: &[&str]) -> Option<usize> {
+    headers.iter().position(|h| names.contains(&h))
+}
+
+impl Columns {
+    pub fn from_headers(headers: &StringRecord) -> Result<Self, String> {
+        Ok(Columns {
+            main_island: position(headers, "MainIsland")?,
+            region: position(headers, "Region")?,
+            province: position(headers, "Province")?,
+            municipality: optional_position(headers, &["Municipality"]),
+            legislative_district: optional_position(headers, &["LegislativeDistrict"]),
+            project_id: optional_position(headers, &["ProjectId"]),
+            contract_id: optional_position(headers, &["ContractId"]),
+            description: optional_position(
+                headers,
+                &["ProjectDescription", "ProjectName", "Description"],
+            ),
+            contractor: position(headers, "Contractor")?,
+            type_of_work: position(headers, "TypeOfWork")?,
+            funding_year: position(headers, "FundingYear")?,
+            approved_budget: position(headers, "ApprovedBudgetForContract")?,
+            contract_cost: position(headers, "ContractCost")?,
+            start_date: position(headers, "StartDate")?,
+            actual_completion_date: position(headers, "ActualCompletionDate")?,
+            target_completion_date: optional_position(
+                headers,
+                &[
+                    "TargetCompletionDate",
+                    "OriginalCompletionDate",
+                    "PlannedCompletionDate",
+                ],
+            ),
+            contract_duration: optional_position(
+                headers,
+                &["ContractDuration", "ContractDurationDays"],
+            ),
+            latitude: optional_position(headers, &["ProjectLatitude", "Latitude"]),
+            longitude: optional_position(headers, &["ProjectLongitude", "Longitude"]),
+        })
+    }
+
+    pub fn has_description(&self) -> bool {
+        self.description.is_some()
+    }
+
+    /// Whether schedule delay can be measured: a target date or contract duration column.
+    pub fn has_target_dates(&self) -> bool {
+        self.target_completion_date.is_some() || self.contract_duration.is_some()
+    }
+}
+
+impl Project {
+    /// `row_number` is the 1-based data row in the source file, used to refer back to it.
+    pub fn from_record(columns: &Columns, record: &StringRecord, row_number: usize) -> Self {
+        let text = |idx: usize| record.get(idx).unwrap_or("").to_string();
+        let optional_text = |idx: Option<usize>| idx.map(text).unwrap_or_default();
+
+        let start_date = parse_date(record.get(columns.start_date).unwrap_or(""));
+
+        // Prefer an explicit target date; otherwise derive it from the contract duration
+        let target_completion_date = columns
+            .target_completion_date
+            .and_then(|idx| parse_date(record.get(idx).unwrap_or("")))
+            .or_else(|| {
+                let days = columns
+                    .contract_duration
+                    .and_then(|idx| record.get(idx))
+                    .and_then(|v| v.trim().parse::<i64>().ok())?;
+                start_date.map(|s| s + Duration::days(days))
+            });
+
+        Project {
+            row_number,
+            main_island: text(columns.main_island),
+            region: text(columns.region),
+            province: text(columns.province),
+            municipality: optional_text(columns.municipality),
+            legislative_district: optional_text(columns.legislative_district),
+            project_id: optional_text(columns.project_id),
+            contract_id: optional_text(columns.contract_id),
+            description: optional_text(columns.description),
+            contractor: text(columns.contractor),
+            raw_contractor: text(columns.contractor),
+            members: vec![ContractorShare {
+                name: text(columns.contractor),
+                share: 1.0,
+            }],
+            firms: vec![text(columns.contractor)],
+            type_of_work: text(columns.type_of_work),
+            funding_year: text(columns.funding_year),
+            approved_budget: parse_f64(record.get(columns.approved_budget).unwrap_or("")),
+            contract_cost: parse_f64(record.get(columns.contract_cost).unwrap_or("")),
+            start_date,
+            actual_completion_date: parse_date(
+                record.get(columns.actual_completion_date).unwrap_or(""),
+            ),
+            target_completion_date,
+            coordinates: Coordinates::parse(
+                &optional_text(columns.latitude),
+                &optional_text(columns.longitude),
+            ),
+        }
+    }
+
+    /// Municipality and province, or just the province when the extract has no municipality.
+    pub fn location(&self) -> String {
+        if self.municipality.is_empty() {
+            self.province.clone()
+        } else {
+            format!("{}, {}", self.municipality, self.province)
+        }
+    }
+
+    /// Whether `name` is the project's contractor or one of its joint-venture members.
+    pub fn involves(&self, name: &str) -> bool {
+        self.contractor == name || self.firms.iter().any(|f| f == name)
+    }
+
+    /// Approved budget minus contract cost; negative when the contract overran.
+    pub fn savings(&self) -> f64 {
+        self.approved_budget - self.contract_cost
+    }
+
+    /// Days from start to actual completion, if both dates parsed.
+    pub fn duration_days(&self) -> Option<i64> {
+        match (self.start_date, self.actual_completion_date) {
+            (Some(s), Some(a)) => Some((a - s).num_days()),
+            _ => None,
+        }
+    }
+
+    /// Days completed past the target date, if the extract carries one.
+    pub fn schedule_delay_days(&self) -> Option<i64> {
+        match (self.target_completion_date, self.actual_completion_date) {
+            (Some(t), Some(a)) => Some((a - t).num_days()),
+            _ => None,
+        }
+    }
+
+    /// Delay under the chosen definition, for completed projects only.
+    pub fn delay_days(&self, definition: DelayDefinition) -> Option<i64> {
+        match definition {
+            DelayDefinition::Duration => self.duration_days(),
+            DelayDefinition::Schedule => self.schedule_delay_days(),
+        }
+    }
+
+    /// The date delay is measured from under the chosen definition.
+    pub fn delay_base_date(&self, definition: DelayDefinition) -> Option<NaiveDate> {
+        match definition {
+            DelayDefinition::Duration => self.start_date,
+            DelayDefinition::Schedule => self.target_completion_date,
+        }
+    }
+
+    pub fn status(&self, reference_date: NaiveDate) -> ProjectStatus {
+        match (self.actual_completion_date, self.start_date) {
+            (Some(_), _) => ProjectStatus::Completed,
+            (None, Some(start)) if start <= reference_date => ProjectStatus::Ongoing,
+            _ => ProjectStatus::Unknown,
+        }
+    }
+
+    fn measure(&self, definition: DelayDefinition, options: &DelayOptions) -> Delay {
+        match self.status(options.reference_date) {
+            ProjectStatus::Completed => self
+                .delay_days(definition)
+                .map(Delay::Observed)
+                .unwrap_or(Delay::Missing),
+            ProjectStatus::Ongoing => {
+                let Some(base) = self.delay_base_date(definition) else {
+                    return Delay::Missing;
+                };
+                let age = (options.reference_date - base).num_days();
+                match options.policy {
+                    IncompletePolicy::Exclude => Delay::Excluded,
+                    IncompletePolicy::Censored => Delay::Censored(age),
+                    IncompletePolicy::Age => Delay::Observed(age),
+                }
+            }
+            ProjectStatus::Unknown => Delay::Missing,
+        }
+    }
+
+    /// Delay under the configured definition and incomplete-project policy.
+    pub fn delay(&self, options: &DelayOptions) -> Delay {
+        self.measure(options.definition, options)
+    }
+
+    /// Duration (start to completion) under the configured incomplete-project policy.
+    pub fn duration(&self, options: &DelayOptions) -> Delay {
+        self.measure(DelayDefinition::Duration, options)
+    }
+
+    /// Schedule delay (target date to completion) under the configured incomplete-project
+    /// policy, whichever delay definition is in use.
+    pub fn schedule_delay(&self, options: &DelayOptions) -> Delay {
+        self.measure(DelayDefinition::Schedule, options)
+    }
+}
+
+impl DelayDefinition {
+    pub fn name(self) -> &'static str {
+        match self {
+            DelayDefinition::Duration => "duration (ActualCompletionDate - StartDate)",
+            DelayDefinition::Schedule => "schedule (ActualCompletionDate - TargetCompletionDate)",
+        }
+    }
+}
+
+impl IncompletePolicy {
+    pub fn name(self) -> &'static str {
+        match self {
+            IncompletePolicy::Exclude => "exclude",
+            IncompletePolicy::Censored => "censored",
+            IncompletePolicy::Age => "age to reference date",
+        }
+    }
+}
+
+impl ProjectStatus {
+    pub fn name(self) -> &'static str {
+        match self {
+            ProjectStatus::Completed => "Completed",
+            ProjectStatus::Ongoing => "Ongoing",
+            ProjectStatus::Unknown => "Unknown",
+        }
+    }
+}
+
+impl Delay {
+    /// The delay if it was actually measured.
+    pub fn observed(self) -> Option<i64> {
+        match self {
+            Delay::Observed(days) => Some(days),
+            _ => None,
+        }
+    }
+
+    /// The delay, or the lower bound of a censored one.
+    pub fn at_least(self) -> Option<i64> {
+        match self {
+            Delay::Observed(days) | Delay::Censored(days) => Some(days),
+            Delay::Excluded | Delay::Missing => None,
+        }
+    }
+
+    /// Mean of the delays that were actually measured; `None` when none were.
+    pub fn mean_observed<'a>(delays: impl Iterator<Item = &'a Delay>) -> Option<f64> {
+        let observed: Vec<i64> = delays.filter_map(|d| d.observed()).collect();
+        (!observed.is_empty()).then(|| observed.iter().sum::<i64>() as f64 / observed.len() as f64)
+    }
+}
+
+// -----------------------------
+// Test fixtures
+// -----------------------------
+#[cfg(test)]
+pub mod fixtures {
+    use super::{Columns, Project};
+    use csv::StringRecord;
+
+    /// A Luzon drainage project funded in 2022, run from January to June of that year.
+    pub fn project(row_number: usize, region: &str, contract_cost: &str) -> Project {
+        let headers = StringRecord::from(vec![
+            "MainIsland",
+            "Region",
+            "Province",
+            "Contractor",
+            "TypeOfWork",
+            "FundingYear",
+            "ApprovedBudgetForContract",
+            "ContractCost",
+            "StartDate",
+            "ActualCompletionDate",
+        ]);
+        let record = StringRecord::from(vec![
+            "Luzon",
+            region,
+            "Province",
+            "Contractor",
+            "Drainage",
+            "2022",
+            contract_cost,
+            contract_cost,
+            "2022-01-01",
+            "2022-06-01",
+        ]);
+        Project::from_record(
+            &Columns::from_headers(&headers).unwrap(),
+            &record,
+            row_number,
+        )
+    }
+}
@@ -0,0 +1,291 @@
+use crate::services::config::ReferenceConfig;
+use crate::services::project::Project;
+use csv::{Reader, StringRecord};
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+/// One keyed row; a column missing from the file leaves its field empty.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReferenceRow {
+    pub population: Option<f64>,
+    pub land_area_km2: Option<f64>,
+    /// Share (0-1) of the land area in flood-hazard zones.
+    pub flood_hazard_index: Option<f64>,
+}
+
+impl ReferenceRow {
+    /// Land area in flood-hazard zones, in hectares.
+    pub fn hazard_exposed_ha(&self) -> Option<f64> {
+        Some(self.land_area_km2? * 100.0 * self.flood_hazard_index?)
+    }
+}
+
+/// Rows are keyed by one or more columns; districts need their province, since district
+/// names such as "1st District" repeat across provinces.
+#[derive(Debug, Clone)]
+pub struct ReferenceTable {
+    pub path: String,
+    /// From the file's `Version` column, "unversioned" without one.
+    pub version: String,
+    /// FNV-1a hash of the file contents, so a changed table shows up in the export metadata.
+    pub checksum: String,
+    rows: HashMap<Vec<String>, ReferenceRow>,
+}
+
+impl ReferenceTable {
+    /// Case- and whitespace-insensitive lookup, one value per key column.
+    pub fn get(&self, key: &[&str]) -> Option<&ReferenceRow> {
+        self.rows.get(&normalise_key(key))
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "{} (version {}, checksum {})",
+            self.path, self.version, self.checksum
+        )
+    }
+}
+
+/// The tables joined onto a dataset; `None` where the file is missing or failed validation.
+#[derive(Debug, Clone, Default)]
+pub struct References {
+    pub regions: Option<ReferenceTable>,
+    pub provinces: Option<ReferenceTable>,
+    pub districts: Option<ReferenceTable>,
+}
+
+impl References {
+    pub fn describe(&self) -> Vec<String> {
+        [&self.regions, &self.provinces, &self.districts]
+            .into_iter()
+            .flatten()
+            .map(|t| t.describe())
+            .collect()
+    }
+}
+
+const VALUE_COLUMNS: [&str; 3] = ["Population", "LandAreaKm2", "FloodHazardIndex"];
+
+// Row errors listed before the rest are summarised as a count
+const MAX_LISTED_ERRORS: usize = 5;
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+/// `total` per unit of `denominator`, rounded to 2 decimals; blank without a positive denominator.
+pub fn per_unit(total: f64, denominator: Option<f64>) -> Option<f64> {
+    denominator
+        .filter(|d| *d > 0.0)
+        .map(|d| (total / d * 100.0).round() / 100.0)
+}
+
+fn normalise_key(key: &[&str]) -> Vec<String> {
+    key.iter().map(|k| k.trim().to_uppercase()).collect()
+}
+
+pub fn fnv1a(bytes: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{hash:016x}")
+}
+
+fn parse_value(
+    record: &StringRecord,
+    column: Option<usize>,
+    name: &str,
+) -> Result<Option<f64>, String> {
+    let Some(text) = column.and_then(|idx| record.get(idx)).map(str::trim) else {
+        return Ok(None);
+    };
+    if text.is_empty() {
+        return Ok(None);
+    }
+
+    let value: f64 = text
+        .replace(",", "")
+        .parse()
+        .ok()
+        .filter(|v: &f64| v.is_finite())
+        .ok_or(format!("{name} \"{text}\" is not a number"))?;
+    if value < 0.0 {
+        return Err(format!("{name} {value} is negative"));
+    }
+    if name == "FloodHazardIndex" && value > 1.0 {
+        return Err(format!("{name} {value} is above 1"));
+    }
+    Ok(Some(value))
+}
+
+/// Reads a reference CSV keyed by `key_columns`. `Ok(None)` when the file does not exist; an
+/// error naming every bad row when a column is missing, a value is invalid, a key repeats or
+/// the `Version` column is not the same throughout.
+pub fn load_table(
+    path: &str,
+    key_columns: &[&str],
+) -> Result<Option<ReferenceTable>, Box<dyn Error>> {
+    if !Path::new(path).exists() {
+        return Ok(None);
+    }
+
+    let bytes = fs::read(path)?;
+    let mut reader = Reader::from_reader(bytes.as_slice());
+    let headers = reader.headers()?.clone();
+    let position = |name: &str| headers.iter().position(|h| h.trim() == name);
+
+    let keys: Vec<usize> = key_columns
+        .iter()
+        .map(|c| position(c).ok_or(format!("Column {c} not found")))
+        .collect::<Result<_, _>>()?;
+    let columns: Vec<Option<usize>> = VALUE_COLUMNS.iter().map(|c| position(c)).collect();
+    if columns.iter().all(Option::is_none) {
+        return Err(format!("None of the columns {} found", VALUE_COLUMNS.join(", ")).into());
+    }
+    let version_column = position("Version");
+
+    let mut rows: HashMap<Vec<String>, ReferenceRow> = HashMap::new();
+    let mut versions: BTreeSet<String> = BTreeSet::new();
+    let mut errors: Vec<String> = Vec::new();
+
+    for (i, result) in reader.records().enumerate() {
+        let record = result?;
+        let line = i + 2;
+        let names: Vec<&str> = keys
+            .iter()
+            .map(|idx| record.get(*idx).unwrap_or("").trim())
+            .collect();
+        if let Some(empty) = names.iter().position(|n| n.is_empty()) {
+            errors.push(format!("line {line}: empty {}", key_columns[empty]));
+            continue;
+        }
+        if let Some(idx) = version_column {
+            versions.insert(record.get(idx).unwrap_or("").trim().to_string());
+        }
+
+        let values: Result<Vec<Option<f64>>, String> = VALUE_COLUMNS
+            .iter()
+            .zip(&columns)
+            .map(|(column, idx)| parse_value(&record, *idx, column))
+            .collect();
+        let values = match values {
+            Ok(values) => values,
+            Err(err) => {
+                errors.push(format!("line {line}: {err}"));
+                continue;
+            }
+        };
+
+        let row = ReferenceRow {
+            population: values[0],
+            land_area_km2: values[1],
+            flood_hazard_index: values[2],
+        };
+        if rows.insert(normalise_key(&names), row).is_some() {
+            errors.push(format!(
+                "line {line}: {} \"{}\" appears more than once",
+                key_columns.join(" / "),
+                names.join(" / ")
+            ));
+        }
+    }
+
+    if versions.len() > 1 {
+        errors.push(format!(
+            "Version differs between rows ({})",
+            versions.into_iter().collect::<Vec<_>>().join(", ")
+        ));
+        versions = BTreeSet::new();
+    }
+    if !errors.is_empty() {
+        let mut message = errors
+            .iter()
+            .take(MAX_LISTED_ERRORS)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("; ");
+        if errors.len() > MAX_LISTED_ERRORS {
+            message.push_str(&format!(" and {} more", errors.len() - MAX_LISTED_ERRORS));
+        }
+        return Err(message.into());
+    }
+
+    Ok(Some(ReferenceTable {
+        path: path.to_string(),
+        version: versions
+            .into_iter()
+            .next()
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| "unversioned".to_string()),
+        checksum: fnv1a(&bytes),
+        rows,
+    }))
+}
+
+/// Loads one table and reports how many of the dataset's keys it covers.
+fn join_table<F>(
+    path: &str,
+    key_columns: &[&str],
+    projects: &[Project],
+    key: F,
+) -> Option<ReferenceTable>
+where
+    F: Fn(&Project) -> Vec<&str>,
+{
+    let table = match load_table(path, key_columns) {
+        Ok(table) => table?,
+        Err(err) => {
+            eprintln!("Error: {path} failed validation ({err}), continuing without it");
+            return None;
+        }
+    };
+
+    let keys: BTreeSet<Vec<&str>> = projects
+        .iter()
+        .map(&key)
+        .filter(|k| k.iter().all(|part| !part.trim().is_empty()))
+        .collect();
+    let missing: Vec<String> = keys
+        .iter()
+        .filter(|k| table.get(k).is_none())
+        .map(|k| k.join(" / "))
+        .collect();
+
+    println!(
+        "Reference {}: {} of {} {} values matched",
+        table.describe(),
+        keys.len() - missing.len(),
+        keys.len(),
+        key_columns.join(" / ")
+    );
+    if !missing.is_empty() {
+        println!("  Not in the table: {}", missing.join(", "));
+    }
+    Some(table)
+}
+
+// -----------------------------
+// Load References
+// -----------------------------
+pub fn load_references(projects: &[Project], config: &ReferenceConfig) -> References {
+    References {
+        regions: join_table(&config.region_file, &["Region"], projects, |p| {
+            vec![&p.region]
+        }),
+        provinces: join_table(&config.province_file, &["Province"], projects, |p| {
+            vec![&p.province]
+        }),
+        districts: join_table(
+            &config.district_file,
+            &["Province", "LegislativeDistrict"],
+            projects,
+            |p| vec![&p.province, &p.legislative_district],
+        ),
+    }
+}
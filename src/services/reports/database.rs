@@ -0,0 +1,20 @@
+use crate::services::config::Config;
+use crate::services::loader::DataSet;
+use crate::services::sql::export_database;
+
+pub const DEFAULT_FILE: &str = "pipeline_results.sqlite";
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+pub fn generate_report(data: &DataSet, config: &Config, path: &str) {
+    println!("Exporting SQLite Database...\n");
+
+    match export_database(path, data, config) {
+        Ok(()) => println!(
+            "{} projects, Reports 1-3 and the summary exported to {path}\n",
+            data.projects.len()
+        ),
+        Err(err) => eprintln!("Error: could not write {path} ({err})\n"),
+    }
+}
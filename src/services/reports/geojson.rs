@@ -0,0 +1,58 @@
+use crate::services::export::export_json;
+use crate::services::loader::DataSet;
+use serde_json::{Value, json};
+
+// -----------------------------
+// Generate GeoJSON
+// -----------------------------
+/// Writes every loaded project with valid coordinates as a GeoJSON point feature.
+pub fn generate_geojson(data: &DataSet) {
+    println!("Generating GeoJSON Export...\n");
+
+    let features: Vec<Value> = data
+        .projects
+        .iter()
+        .filter_map(|project| {
+            let point = project.coordinates?;
+            let delay = project.delay(&data.delay);
+            Some(json!({
+                "type": "Feature",
+                // GeoJSON positions are longitude first
+                "geometry": {
+                    "type": "Point",
+                    "coordinates": [point.longitude, point.latitude],
+                },
+                "properties": {
+                    "row_number": project.row_number,
+                    "project_id": project.project_id,
+                    "contract_id": project.contract_id,
+                    "contractor": project.contractor,
+                    "region": project.region,
+                    "province": project.province,
+                    "municipality": project.municipality,
+                    "type_of_work": project.type_of_work,
+                    "funding_year": project.funding_year,
+                    "approved_budget": project.approved_budget,
+                    "contract_cost": project.contract_cost,
+                    "savings": project.savings(),
+                    "delay": delay.observed(),
+                    "delay_at_least": delay.at_least(),
+                    "status": project.status(data.delay.reference_date).name(),
+                },
+            }))
+        })
+        .collect();
+
+    let skipped = data.projects.len() - features.len();
+    let collection = json!({
+        "type": "FeatureCollection",
+        "features": features,
+    });
+
+    export_json("projects.geojson", &collection, data);
+
+    println!(
+        "{} projects exported to projects.geojson ({skipped} without valid coordinates left out)\n",
+        data.projects.len() - skipped
+    );
+}
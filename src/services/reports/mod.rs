@@ -1,4 +1,21 @@
+pub mod database;
+pub mod geojson;
+pub mod pivot;
+pub mod query;
 pub mod report1;
+pub mod report10;
+pub mod report11;
+pub mod report12;
+pub mod report13;
+pub mod report14;
+pub mod report15;
+pub mod report16;
 pub mod report2;
 pub mod report3;
+pub mod report4;
+pub mod report5;
+pub mod report6;
+pub mod report7;
+pub mod report8;
+pub mod report9;
 pub mod summary;
@@ -0,0 +1,322 @@
+use crate::services::export::export_records;
+use crate::services::fields::{Field, Kind};
+use crate::services::loader::DataSet;
+use crate::services::project::Project;
+use crate::services::stats::{median, quantile, sorted};
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, Write};
+use tabled::builder::Builder;
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+/// What to group by and what to compute per group, e.g. `Region, TypeOfWork` and
+/// `count, sum(ContractCost), p90(Delay)`.
+#[derive(Debug, Clone)]
+pub struct PivotSpec {
+    dimensions: Vec<Field>,
+    metrics: Vec<Metric>,
+}
+
+#[derive(Debug, Clone)]
+struct Metric {
+    label: String,
+    aggregate: Aggregate,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Aggregate {
+    Count,
+    Distinct(Field),
+    Sum(Field),
+    Mean(Field),
+    Median(Field),
+    Min(Field),
+    Max(Field),
+    /// Percentile in 0-100.
+    Percentile(f64, Field),
+    /// Percentage of projects with ContractCost above the approved budget.
+    OverrunRate,
+}
+
+const METRIC_HELP: &str = "count, distinct(<field>), sum/mean/median/min/max(<number field>), p<0-100>(<number field>), overrun_rate";
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn split_list(text: &str) -> impl Iterator<Item = &str> {
+    text.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
+fn parse_field(name: &str) -> Result<Field, String> {
+    Field::lookup(name).ok_or_else(|| {
+        format!(
+            "unknown field {name} (known: {})",
+            Field::names().join(", ")
+        )
+    })
+}
+
+fn parse_metric(spec: &str) -> Result<Metric, String> {
+    let lower = spec.to_lowercase();
+    match lower.as_str() {
+        "count" => {
+            return Ok(Metric {
+                label: "count".to_string(),
+                aggregate: Aggregate::Count,
+            });
+        }
+        "overrun_rate" => {
+            return Ok(Metric {
+                label: "overrun_rate".to_string(),
+                aggregate: Aggregate::OverrunRate,
+            });
+        }
+        _ => {}
+    }
+
+    let (function, argument) = lower
+        .strip_suffix(')')
+        .and_then(|s| s.split_once('('))
+        .ok_or_else(|| format!("\"{spec}\" is not a metric ({METRIC_HELP})"))?;
+    let field = parse_field(argument)?;
+    let function = function.trim();
+
+    let aggregate = match function {
+        "distinct" => Aggregate::Distinct(field),
+        _ if field.kind() != Kind::Number => {
+            return Err(format!(
+                "{function} needs a number field, not {}",
+                field.name()
+            ));
+        }
+        "sum" => Aggregate::Sum(field),
+        "mean" | "avg" => Aggregate::Mean(field),
+        "median" => Aggregate::Median(field),
+        "min" => Aggregate::Min(field),
+        "max" => Aggregate::Max(field),
+        _ => match function.strip_prefix('p').map(str::parse::<f64>) {
+            Some(Ok(p)) if (0.0..=100.0).contains(&p) => Aggregate::Percentile(p, field),
+            _ => return Err(format!("\"{spec}\" is not a metric ({METRIC_HELP})")),
+        },
+    };
+
+    Ok(Metric {
+        label: format!("{function}({})", field.name()),
+        aggregate,
+    })
+}
+
+impl PivotSpec {
+    /// `dimensions` and `metrics` are comma-separated; no dimensions gives one total row.
+    pub fn parse(dimensions: &str, metrics: &str) -> Result<PivotSpec, String> {
+        let dimensions = split_list(dimensions)
+            .map(parse_field)
+            .collect::<Result<Vec<_>, _>>()?;
+        let metrics = split_list(metrics)
+            .map(parse_metric)
+            .collect::<Result<Vec<_>, _>>()?;
+        if metrics.is_empty() {
+            return Err(format!("at least one metric is needed ({METRIC_HELP})"));
+        }
+        Ok(PivotSpec {
+            dimensions,
+            metrics,
+        })
+    }
+}
+
+/// `None` when the group has no values for the metric's field.
+fn evaluate(aggregate: Aggregate, projects: &[&Project], data: &DataSet) -> Option<f64> {
+    let values = |field: Field| -> Vec<f64> {
+        projects
+            .iter()
+            .filter_map(|p| field.number(p, &data.delay))
+            .collect()
+    };
+    let non_empty = |v: Vec<f64>| (!v.is_empty()).then_some(v);
+
+    match aggregate {
+        Aggregate::Count => Some(projects.len() as f64),
+        Aggregate::Distinct(field) => {
+            let distinct: HashSet<String> = projects
+                .iter()
+                .map(|p| field.display(p, &data.delay))
+                .filter(|v| !v.is_empty())
+                .collect();
+            Some(distinct.len() as f64)
+        }
+        Aggregate::Sum(field) => non_empty(values(field)).map(|v| v.iter().sum()),
+        Aggregate::Mean(field) => {
+            non_empty(values(field)).map(|v| v.iter().sum::<f64>() / v.len() as f64)
+        }
+        Aggregate::Median(field) => non_empty(values(field)).map(|v| median(&v)),
+        Aggregate::Min(field) => {
+            non_empty(values(field)).map(|v| v.into_iter().fold(f64::MAX, f64::min))
+        }
+        Aggregate::Max(field) => {
+            non_empty(values(field)).map(|v| v.into_iter().fold(f64::MIN, f64::max))
+        }
+        Aggregate::Percentile(p, field) => {
+            non_empty(values(field)).map(|v| quantile(&sorted(&v), p / 100.0))
+        }
+        Aggregate::OverrunRate => {
+            let overruns = projects.iter().filter(|p| p.savings() < 0.0).count();
+            (!projects.is_empty()).then(|| overruns as f64 / projects.len() as f64 * 100.0)
+        }
+    }
+}
+
+fn ask_line(prompt: &str) -> String {
+    print!("{prompt}");
+    io::stdout().flush().unwrap();
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    println!();
+    input.trim().to_string()
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+/// Menu entry: asks for the dimensions and metrics until they parse, then runs the pivot.
+pub fn prompt_report(data: &DataSet) {
+    println!("Fields: {}", Field::names().join(", "));
+    println!("Metrics: {METRIC_HELP}\n");
+
+    loop {
+        let dimensions = ask_line("Group by (comma-separated, Enter for totals): ");
+        let metrics = ask_line("Metrics (comma-separated): ");
+        match PivotSpec::parse(&dimensions, &metrics) {
+            Ok(spec) => return generate_report(data, &spec),
+            Err(err) => println!("Invalid pivot: {err}\n"),
+        }
+    }
+}
+
+pub fn generate_report(data: &DataSet, spec: &PivotSpec) {
+    println!("Generating Pivot Table...\n");
+
+    let mut groups: BTreeMap<Vec<String>, Vec<&Project>> = BTreeMap::new();
+    for project in &data.projects {
+        let key: Vec<String> = spec
+            .dimensions
+            .iter()
+            .map(|d| d.display(project, &data.delay))
+            .collect();
+        groups.entry(key).or_default().push(project);
+    }
+
+    let mut results: Vec<(Vec<String>, Vec<Option<f64>>)> = groups
+        .into_iter()
+        .map(|(key, projects)| {
+            let values = spec
+                .metrics
+                .iter()
+                .map(|m| evaluate(m.aggregate, &projects, data))
+                .collect();
+            (key, values)
+        })
+        .collect();
+
+    // Sort descending by the first metric; groups without a value go last
+    results.sort_by(|a, b| {
+        b.1[0]
+            .unwrap_or(f64::MIN)
+            .partial_cmp(&a.1[0].unwrap_or(f64::MIN))
+            .unwrap()
+    });
+
+    let headers: Vec<String> = spec
+        .dimensions
+        .iter()
+        .map(|d| d.name().to_string())
+        .chain(spec.metrics.iter().map(|m| m.label.clone()))
+        .collect();
+    // Values are rounded for the console only; the export keeps full precision
+    let cells = |round: fn(f64) -> f64| -> Vec<Vec<String>> {
+        results
+            .iter()
+            .map(|(key, values)| {
+                key.iter()
+                    .cloned()
+                    .chain(
+                        values
+                            .iter()
+                            .map(|v| v.map(|v| round(v).to_string()).unwrap_or_default()),
+                    )
+                    .collect()
+            })
+            .collect()
+    };
+
+    let mut builder = Builder::default().set_header(headers.clone());
+    for row in cells(round2) {
+        builder = builder.add_row(row);
+    }
+    println!("{}", builder.build());
+
+    export_records("pivot_table.csv", &headers, &cells(|v| v), data);
+
+    println!("Full table exported to pivot_table.csv\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(spec: &PivotSpec) -> (Vec<&str>, Vec<&str>) {
+        (
+            spec.dimensions.iter().map(|d| d.name()).collect(),
+            spec.metrics.iter().map(|m| m.label.as_str()).collect(),
+        )
+    }
+
+    #[test]
+    fn parses_dimensions_and_metrics() {
+        let spec = PivotSpec::parse(
+            "region, type_of_work",
+            "count, SUM(ContractCost), avg(contract_cost), p90(Delay), distinct(Contractor)",
+        )
+        .unwrap();
+        assert_eq!(
+            labels(&spec),
+            (
+                vec!["Region", "TypeOfWork"],
+                vec![
+                    "count",
+                    "sum(ContractCost)",
+                    "avg(ContractCost)",
+                    "p90(Delay)",
+                    "distinct(Contractor)"
+                ]
+            )
+        );
+        assert!(matches!(
+            spec.metrics[3].aggregate,
+            Aggregate::Percentile(p, Field::Delay) if p == 90.0
+        ));
+
+        // No dimensions gives a single total row
+        let spec = PivotSpec::parse("", "overrun_rate").unwrap();
+        assert_eq!(labels(&spec), (vec![], vec!["overrun_rate"]));
+    }
+
+    #[test]
+    fn rejects_invalid_specs() {
+        let err = PivotSpec::parse("Region", "").unwrap_err();
+        assert!(err.starts_with("at least one metric"), "{err}");
+        let err = PivotSpec::parse("Nope", "count").unwrap_err();
+        assert!(err.starts_with("unknown field Nope"), "{err}");
+        let err = PivotSpec::parse("", "sum(Region)").unwrap_err();
+        assert_eq!(err, "sum needs a number field, not Region");
+        let err = PivotSpec::parse("", "p101(ContractCost)").unwrap_err();
+        assert!(err.contains("is not a metric"), "{err}");
+        let err = PivotSpec::parse("", "total(ContractCost)").unwrap_err();
+        assert!(err.contains("is not a metric"), "{err}");
+    }
+}
@@ -0,0 +1,89 @@
+use crate::services::export::export_records;
+use crate::services::loader::DataSet;
+use crate::services::sql::{display_value, export_value, open_projects, run_query};
+use rusqlite::Connection;
+use std::io::{self, Write};
+use tabled::builder::Builder;
+
+// Rows printed to the console; the export has all of them
+const MAX_PRINTED_ROWS: usize = 50;
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+/// Reads lines until one ends with ";" or is blank; `None` when nothing was entered.
+fn ask_sql() -> Option<String> {
+    print!("SQL (end with ; or a blank line, blank to go back): ");
+    io::stdout().flush().unwrap();
+
+    let mut sql = String::new();
+    loop {
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line).unwrap() == 0 || line.trim().is_empty() {
+            break;
+        }
+        sql.push_str(&line);
+        if line.trim_end().ends_with(';') {
+            break;
+        }
+    }
+    println!();
+
+    let sql = sql.trim().trim_end_matches(';').trim().to_string();
+    (!sql.is_empty()).then_some(sql)
+}
+
+fn show_result(conn: &Connection, sql: &str, data: &DataSet) {
+    let (headers, rows) = match run_query(conn, sql) {
+        Ok(result) => result,
+        Err(err) => {
+            println!("Query failed: {err}\n");
+            return;
+        }
+    };
+
+    let mut builder = Builder::default().set_header(headers.clone());
+    for row in rows.iter().take(MAX_PRINTED_ROWS) {
+        builder = builder.add_row(row.iter().map(display_value).collect::<Vec<_>>());
+    }
+    println!("{}", builder.build());
+    if rows.len() > MAX_PRINTED_ROWS {
+        println!("First {MAX_PRINTED_ROWS} of {} rows shown", rows.len());
+    }
+
+    let records: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(export_value).collect())
+        .collect();
+    export_records("query_result.csv", &headers, &records, data);
+
+    println!("Result exported to query_result.csv\n");
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+/// Menu entry: runs queries against the loaded projects until a blank one is entered.
+pub fn prompt_report(data: &DataSet) {
+    let conn = match open_projects(data) {
+        Ok(conn) => conn,
+        Err(err) => {
+            eprintln!("Error: could not build the query database ({err})\n");
+            return;
+        }
+    };
+    println!("Tables: projects, project_members (see README for the columns)\n");
+
+    while let Some(sql) = ask_sql() {
+        show_result(&conn, &sql, data);
+    }
+}
+
+pub fn generate_report(data: &DataSet, sql: &str) {
+    println!("Running SQL Query...\n");
+
+    match open_projects(data) {
+        Ok(conn) => show_result(&conn, sql, data),
+        Err(err) => eprintln!("Error: could not build the query database ({err})\n"),
+    }
+}
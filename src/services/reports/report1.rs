@@ -1,5 +1,10 @@
+use crate::services::bootstrap::{intervals, overlaps_neighbour};
+use crate::services::config::{Config, EfficiencyConfig, EfficiencyFormula};
+use crate::services::export::{display_option, export_csv};
 use crate::services::loader::DataSet;
-use chrono::NaiveDate;
+use crate::services::project::{Delay, Project};
+use crate::services::reference::per_unit;
+use crate::services::stats::{percentile_ranks, z_scores};
 use serde::Serialize;
 use std::collections::HashMap;
 use tabled::{Table, Tabled};
@@ -13,29 +18,53 @@ pub struct EfficiencyReportRow {
     pub region: String,
     pub total_budget: f64,
     pub median_savings: f64,
+    pub savings_pct: f64,
+    pub avg_duration: f64,
     pub avg_delay: f64,
     pub delayed_over_30_pct: f64,
+    /// Blank for a region without target completion dates.
+    #[tabled(display_with = "display_option")]
+    pub on_time_pct: Option<f64>,
     pub efficiency_score: f64,
+    /// Ongoing projects left out of the delay figures by the `exclude` policy.
+    pub incomplete_excluded: usize,
+    /// Days past the target date, whichever delay definition is in use; empty without target dates.
+    #[tabled(display_with = "display_option")]
+    pub avg_schedule_delay: Option<f64>,
+    /// Projects without the dates needed for a delay, or with unknown status.
+    pub delay_missing: usize,
+    #[tabled(display_with = "display_option")]
+    pub budget_per_capita: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub budget_per_hazard_ha: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub median_savings_lower_95: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub median_savings_upper_95: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub avg_delay_lower_95: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub avg_delay_upper_95: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub efficiency_score_lower_95: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub efficiency_score_upper_95: Option<f64>,
+    /// "Y" when the score interval overlaps a neighbouring rank's.
+    pub rank_overlap: String,
 }
 
+type RegionGroups = HashMap<(String, String), Vec<(f64, f64, Delay, Delay, Option<i64>, Delay)>>;
+
 // -----------------------------
 // Utility Functions
 // -----------------------------
-fn parse_f64(v: &str) -> f64 {
-    v.replace(",", "").parse::<f64>().unwrap_or(0.0)
-}
-
-fn parse_date(v: &str) -> Option<NaiveDate> {
-    NaiveDate::parse_from_str(v.trim(), "%Y-%m-%d").ok()
-}
-
-fn median(values: &mut Vec<f64>) -> f64 {
+fn median(values: &mut [f64]) -> f64 {
     if values.is_empty() {
         return 0.0;
     }
     values.sort_by(|a, b| a.partial_cmp(b).unwrap());
     let mid = values.len() / 2;
-    if values.len() % 2 == 0 {
+    if values.len().is_multiple_of(2) {
         (values[mid - 1] + values[mid]) / 2.0
     } else {
         values[mid]
@@ -54,106 +83,277 @@ fn round2(value: f64) -> f64 {
     (value * 100.0).round() / 100.0
 }
 
+fn weighted_mean(parts: &[(f64, f64)]) -> f64 {
+    let total_weight: f64 = parts.iter().map(|(_, w)| w).sum();
+    if total_weight == 0.0 {
+        0.0
+    } else {
+        parts.iter().map(|(v, w)| v * w).sum::<f64>() / total_weight
+    }
+}
+
 // -----------------------------
-// Main Report Generator
+// Efficiency Score Formulas
 // -----------------------------
-pub fn generate_report(data: &DataSet) {
-    println!("Generating Efficiency Report...\n");
-
-    let headers = &data.headers;
-
-    let idx_main_island = headers.iter().position(|h| h == "MainIsland").unwrap();
-    let idx_region = headers.iter().position(|h| h == "Region").unwrap();
-    let idx_budget = headers
+/// Median savings over average delay, normalised so the best region scores 100.
+/// Unstable for small delays, and a region with no measured delay scores 0.
+fn ratio_scores(rows: &[EfficiencyReportRow]) -> Vec<f64> {
+    let raw: Vec<f64> = rows
         .iter()
-        .position(|h| h == "ApprovedBudgetForContract")
-        .unwrap();
-    let idx_cost = headers.iter().position(|h| h == "ContractCost").unwrap();
-    let idx_start = headers.iter().position(|h| h == "StartDate").unwrap();
-    let idx_actual = headers
-        .iter()
-        .position(|h| h == "ActualCompletionDate")
-        .unwrap();
+        .map(|r| {
+            if r.avg_delay == 0.0 {
+                0.0
+            } else {
+                (r.median_savings / r.avg_delay) * 100.0
+            }
+        })
+        .collect();
+    let max_score = raw.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    raw.iter()
+        .map(|s| normalize_to_100(*s, max_score))
+        .collect()
+}
 
-    let mut groups: HashMap<(String, String), Vec<(f64, f64, i32)>> = HashMap::new();
+/// Weighted mean of standardised components, each oriented so higher is better. Without
+/// target dates `on_time_pct` is blank everywhere, so it is dropped and the others reweighted.
+fn composite_scores(
+    rows: &[EfficiencyReportRow],
+    config: &EfficiencyConfig,
+    standardise: fn(&[f64]) -> Vec<f64>,
+    has_targets: bool,
+) -> Vec<f64> {
+    let on_time_weight = if has_targets {
+        config.on_time_weight
+    } else {
+        0.0
+    };
+    let savings = standardise(&rows.iter().map(|r| r.savings_pct).collect::<Vec<_>>());
+    let on_time = standardise(
+        &rows
+            .iter()
+            .map(|r| r.on_time_pct.unwrap_or(0.0))
+            .collect::<Vec<_>>(),
+    );
+    let delay = standardise(&rows.iter().map(|r| -r.avg_delay).collect::<Vec<_>>());
 
-    for record in &data.matching_records {
-        let main_island = record.get(idx_main_island).unwrap_or("").to_string();
-        let region = record.get(idx_region).unwrap_or("").to_string();
+    (0..rows.len())
+        .map(|i| {
+            weighted_mean(&[
+                (savings[i], config.savings_weight),
+                (on_time[i], on_time_weight),
+                (delay[i], config.delay_weight),
+            ])
+        })
+        .collect()
+}
+
+fn efficiency_scores(
+    rows: &[EfficiencyReportRow],
+    config: &EfficiencyConfig,
+    has_targets: bool,
+) -> Vec<f64> {
+    match config.formula {
+        EfficiencyFormula::Ratio => ratio_scores(rows),
+        EfficiencyFormula::Zscore => composite_scores(rows, config, z_scores, has_targets),
+        EfficiencyFormula::Rank => composite_scores(rows, config, percentile_ranks, has_targets),
+    }
+}
 
-        let budget = parse_f64(record.get(idx_budget).unwrap_or(""));
-        let cost = parse_f64(record.get(idx_cost).unwrap_or(""));
-        let savings = budget - cost;
+/// Whether any project has a target completion date, without which `on_time_pct` is unknown.
+pub fn has_target_dates(data: &DataSet) -> bool {
+    data.projects
+        .iter()
+        .any(|p| p.target_completion_date.is_some())
+}
 
-        let start = parse_date(record.get(idx_start).unwrap_or(""));
-        let actual = parse_date(record.get(idx_actual).unwrap_or(""));
+/// Warns when the composite formulas have to drop the on-time component.
+pub fn warn_without_targets(data: &DataSet) {
+    if !has_target_dates(data) {
+        println!(
+            "Warning: no target completion dates in the file, so on_time_pct is blank and left out of the zscore and rank efficiency scores\n"
+        );
+    }
+}
 
-        let delay_days = match (start, actual) {
-            (Some(s), Some(a)) => (a - s).num_days() as i32,
-            _ => 0,
-        };
+// -----------------------------
+// Aggregation
+// -----------------------------
+/// One row per region of `projects` (all of `data`'s, or a bootstrap sample), by efficiency
+/// score descending.
+pub fn efficiency_rows<'a>(
+    data: &DataSet,
+    projects: impl IntoIterator<Item = &'a Project>,
+    config: &EfficiencyConfig,
+) -> Vec<EfficiencyReportRow> {
+    let mut groups: RegionGroups = HashMap::new();
 
+    for project in projects {
         groups
-            .entry((main_island, region))
+            .entry((project.main_island.clone(), project.region.clone()))
             .or_default()
-            .push((budget, savings, delay_days));
+            .push((
+                project.approved_budget,
+                project.savings(),
+                project.delay(&data.delay),
+                project.duration(&data.delay),
+                project.schedule_delay_days(),
+                project.schedule_delay(&data.delay),
+            ));
     }
 
     let mut rows: Vec<EfficiencyReportRow> = Vec::new();
-    let mut efficiency_scores: Vec<f64> = Vec::new();
 
     for ((main_island, region), entries) in groups {
         let total_budget: f64 = entries.iter().map(|e| e.0).sum();
         let mut savings_list: Vec<f64> = entries.iter().map(|e| e.1).collect();
+        let total_savings: f64 = savings_list.iter().sum();
         let med_savings = median(&mut savings_list);
 
-        let avg_delay: f64 = {
-            let sum: i64 = entries.iter().map(|e| e.2 as i64).sum();
-            sum as f64 / entries.len() as f64
+        let avg_delay = Delay::mean_observed(entries.iter().map(|e| &e.2)).unwrap_or(0.0);
+        let avg_duration = Delay::mean_observed(entries.iter().map(|e| &e.3)).unwrap_or(0.0);
+        let avg_schedule_delay = Delay::mean_observed(entries.iter().map(|e| &e.5)).map(round2);
+        let incomplete_excluded = entries.iter().filter(|e| e.2 == Delay::Excluded).count();
+        let delay_missing = entries.iter().filter(|e| e.2 == Delay::Missing).count();
+
+        // A censored delay already past 30 days counts as delayed
+        let known: Vec<i64> = entries.iter().filter_map(|e| e.2.at_least()).collect();
+        let delayed_over_30 = if known.is_empty() {
+            0.0
+        } else {
+            known.iter().filter(|d| **d > 30).count() as f64 / known.len() as f64 * 100.0
         };
 
-        let delayed_over_30 =
-            entries.iter().filter(|e| e.2 > 30).count() as f64 / entries.len() as f64 * 100.0;
+        // Completed by the target date, whichever delay definition is in use
+        let scheduled: Vec<i64> = entries.iter().filter_map(|e| e.4).collect();
+        let on_time = (!scheduled.is_empty()).then(|| {
+            scheduled.iter().filter(|d| **d <= 0).count() as f64 / scheduled.len() as f64 * 100.0
+        });
 
-        let raw_eff_score = if avg_delay == 0.0 {
+        let savings_pct = if total_budget == 0.0 {
             0.0
         } else {
-            (med_savings / avg_delay) * 100.0
+            total_savings / total_budget * 100.0
         };
 
-        efficiency_scores.push(raw_eff_score);
+        let reference = data
+            .reference
+            .regions
+            .as_ref()
+            .and_then(|r| r.get(&[&region]));
 
         rows.push(EfficiencyReportRow {
+            budget_per_capita: per_unit(total_budget, reference.and_then(|r| r.population)),
+            budget_per_hazard_ha: per_unit(
+                total_budget,
+                reference.and_then(|r| r.hazard_exposed_ha()),
+            ),
             main_island,
             region,
             total_budget: round2(total_budget),
-            median_savings: round2(med_savings),
-            avg_delay: round2(avg_delay),
+            // Rounded after scoring, so the scores use the exact values
+            median_savings: med_savings,
+            median_savings_lower_95: None,
+            median_savings_upper_95: None,
+            savings_pct,
+            avg_duration: round2(avg_duration),
+            avg_delay,
+            avg_delay_lower_95: None,
+            avg_delay_upper_95: None,
             delayed_over_30_pct: round2(delayed_over_30),
-            efficiency_score: raw_eff_score, // normalized later
+            on_time_pct: on_time,
+            efficiency_score: 0.0, // scored below, across all regions
+            efficiency_score_lower_95: None,
+            efficiency_score_upper_95: None,
+            rank_overlap: String::new(),
+            incomplete_excluded,
+            avg_schedule_delay,
+            delay_missing,
         });
     }
 
-    let max_score = efficiency_scores
-        .iter()
-        .cloned()
-        .fold(f64::NEG_INFINITY, f64::max);
-
-    for row in &mut rows {
-        row.efficiency_score = round2(normalize_to_100(row.efficiency_score, max_score));
+    let scores = efficiency_scores(&rows, config, has_target_dates(data));
+    for (row, score) in rows.iter_mut().zip(scores) {
+        row.efficiency_score = round2(score);
+        row.median_savings = round2(row.median_savings);
+        row.savings_pct = round2(row.savings_pct);
+        row.avg_delay = round2(row.avg_delay);
+        row.on_time_pct = row.on_time_pct.map(round2);
     }
 
     // Sort descending by efficiency_score
     rows.sort_by(|a, b| b.efficiency_score.partial_cmp(&a.efficiency_score).unwrap());
 
+    rows
+}
+
+/// Fills the 95% interval columns by resampling projects within each region.
+fn add_intervals(rows: &mut [EfficiencyReportRow], data: &DataSet, config: &Config) {
+    let bounds = intervals(
+        data,
+        &config.bootstrap,
+        |p| p.region.clone(),
+        |sample| {
+            efficiency_rows(data, sample.iter().copied(), &config.efficiency)
+                .into_iter()
+                .map(|r| {
+                    let values = vec![r.median_savings, r.avg_delay, r.efficiency_score];
+                    (r.region, values)
+                })
+                .collect()
+        },
+    );
+
+    for row in rows.iter_mut() {
+        if let Some(b) = bounds.get(&row.region) {
+            row.median_savings_lower_95 = Some(round2(b[0].0));
+            row.median_savings_upper_95 = Some(round2(b[0].1));
+            row.avg_delay_lower_95 = Some(round2(b[1].0));
+            row.avg_delay_upper_95 = Some(round2(b[1].1));
+            row.efficiency_score_lower_95 = Some(round2(b[2].0));
+            row.efficiency_score_upper_95 = Some(round2(b[2].1));
+        }
+    }
+
+    let score_bounds: Vec<(f64, f64)> = rows
+        .iter()
+        .map(|r| {
+            (
+                r.efficiency_score_lower_95.unwrap_or(r.efficiency_score),
+                r.efficiency_score_upper_95.unwrap_or(r.efficiency_score),
+            )
+        })
+        .collect();
+    for (row, overlap) in rows.iter_mut().zip(overlaps_neighbour(&score_bounds)) {
+        row.rank_overlap = if overlap { "Y" } else { "" }.to_string();
+    }
+}
+
+/// One row per region as printed and exported, with intervals when bootstrap is enabled.
+pub fn report_rows(data: &DataSet, config: &Config) -> Vec<EfficiencyReportRow> {
+    let mut rows = efficiency_rows(data, &data.projects, &config.efficiency);
+
+    if config.bootstrap.enabled {
+        add_intervals(&mut rows, data, config);
+    }
+    rows
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+pub fn generate_report(data: &DataSet, config: &Config) {
+    println!("Generating Efficiency Report...\n");
+    println!("Efficiency score: {}\n", config.efficiency.formula.name());
+    if config.efficiency.formula != EfficiencyFormula::Ratio {
+        warn_without_targets(data);
+    }
+
+    let rows = report_rows(data, config);
+
     let table = Table::new(rows.clone());
     println!("{table}");
 
-    let mut wtr = csv::Writer::from_path("report1_regional_summary.csv").unwrap();
-    for row in rows {
-        wtr.serialize(row).unwrap();
-    }
-    wtr.flush().unwrap();
+    export_csv("report1_regional_summary.csv", &rows, data);
 
     println!("Full table exported to  report1_regional_summary.csv\n");
 }
@@ -0,0 +1,383 @@
+use crate::services::config::Config;
+use crate::services::export::{display_option, export_csv};
+use crate::services::loader::DataSet;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use tabled::{Table, Tabled};
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct ForecastReportRow {
+    pub type_of_work: String,
+    pub metric: String,
+    pub method: String,
+    pub history_years: usize,
+    pub forecast_year: i32,
+    pub forecast: f64,
+    /// Blank when the history is too short to estimate an interval.
+    #[tabled(display_with = "display_option")]
+    pub lower_95: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub upper_95: Option<f64>,
+    pub note: String,
+}
+
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct BacktestReportRow {
+    pub type_of_work: String,
+    pub metric: String,
+    pub method: String,
+    pub holdout_year: i32,
+    pub forecast: f64,
+    pub actual: f64,
+    pub abs_error: f64,
+    /// Blank when the actual value is 0; such years are left out of the MAPE.
+    #[tabled(display_with = "display_option")]
+    pub pct_error: Option<f64>,
+    pub within_interval: bool,
+}
+
+struct Forecast {
+    value: f64,
+    /// 95% prediction interval, when the history is long enough to estimate one.
+    interval: Option<(f64, f64)>,
+}
+
+#[derive(Clone, Copy)]
+enum Method {
+    LinearTrend,
+    ExponentialSmoothing,
+}
+
+impl Method {
+    const ALL: [Method; 2] = [Method::LinearTrend, Method::ExponentialSmoothing];
+
+    fn name(self) -> &'static str {
+        match self {
+            Method::LinearTrend => "Linear Trend",
+            Method::ExponentialSmoothing => "Exponential Smoothing",
+        }
+    }
+}
+
+/// Yearly history of one metric for one TypeOfWork, in year order.
+type History = Vec<(i32, f64)>;
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Two-sided 95% Student t critical value.
+fn t_critical(df: usize) -> f64 {
+    const TABLE: [f64; 10] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228,
+    ];
+    match df {
+        0 => f64::NAN,
+        1..=10 => TABLE[df - 1],
+        _ => 1.96,
+    }
+}
+
+/// Ordinary least squares on year; the interval needs at least three years.
+fn linear_trend(history: &History, target_year: i32) -> Forecast {
+    let n = history.len() as f64;
+    let x_mean = history.iter().map(|(x, _)| *x as f64).sum::<f64>() / n;
+    let y_mean = history.iter().map(|(_, y)| *y).sum::<f64>() / n;
+    let sxx: f64 = history
+        .iter()
+        .map(|(x, _)| (*x as f64 - x_mean).powi(2))
+        .sum();
+
+    if history.len() < 2 || sxx == 0.0 {
+        return Forecast {
+            value: y_mean,
+            interval: None,
+        };
+    }
+
+    let slope = history
+        .iter()
+        .map(|(x, y)| (*x as f64 - x_mean) * (y - y_mean))
+        .sum::<f64>()
+        / sxx;
+    let intercept = y_mean - slope * x_mean;
+    let x0 = target_year as f64;
+    let value = intercept + slope * x0;
+
+    let df = history.len() - 2;
+    let interval = (df > 0).then(|| {
+        let sse: f64 = history
+            .iter()
+            .map(|(x, y)| (y - (intercept + slope * *x as f64)).powi(2))
+            .sum();
+        let s = (sse / df as f64).sqrt();
+        let se = s * (1.0 + 1.0 / n + (x0 - x_mean).powi(2) / sxx).sqrt();
+        let margin = t_critical(df) * se;
+        (value - margin, value + margin)
+    });
+
+    Forecast { value, interval }
+}
+
+/// Simple exponential smoothing; the interval uses the one-step-ahead errors.
+fn exponential_smoothing(history: &History, alpha: f64) -> Forecast {
+    let mut level = history[0].1;
+    let mut squared_errors = Vec::new();
+
+    for (_, y) in &history[1..] {
+        squared_errors.push((y - level).powi(2));
+        level = alpha * y + (1.0 - alpha) * level;
+    }
+
+    let interval = (!squared_errors.is_empty()).then(|| {
+        let sigma = (squared_errors.iter().sum::<f64>() / squared_errors.len() as f64).sqrt();
+        (level - 1.96 * sigma, level + 1.96 * sigma)
+    });
+
+    Forecast {
+        value: level,
+        interval,
+    }
+}
+
+fn forecast(method: Method, history: &History, target_year: i32, alpha: f64) -> Forecast {
+    match method {
+        Method::LinearTrend => linear_trend(history, target_year),
+        Method::ExponentialSmoothing => exponential_smoothing(history, alpha),
+    }
+}
+
+/// Keeps forecasts inside the range a metric can take.
+fn clamp_metric(metric: &str, value: f64) -> f64 {
+    match metric {
+        "total_projects" => value.max(0.0),
+        "overrun_rate" => value.clamp(0.0, 100.0),
+        _ => value,
+    }
+}
+
+/// One yearly history per (TypeOfWork, metric), using report3's metrics but unrounded.
+fn histories(data: &DataSet) -> BTreeMap<(String, &'static str), History> {
+    let mut groups: HashMap<(i32, &str), Vec<f64>> = HashMap::new();
+    for project in &data.projects {
+        if let Ok(year) = project.funding_year.trim().parse::<i32>() {
+            groups
+                .entry((year, &project.type_of_work))
+                .or_default()
+                .push(project.savings());
+        }
+    }
+
+    let mut histories: BTreeMap<(String, &'static str), History> = BTreeMap::new();
+    for ((year, type_of_work), savings) in groups {
+        let n = savings.len() as f64;
+        let overruns = savings.iter().filter(|s| **s < 0.0).count() as f64;
+        let metrics = [
+            ("total_projects", n),
+            ("avg_savings", savings.iter().sum::<f64>() / n),
+            ("overrun_rate", overruns / n * 100.0),
+        ];
+        for (metric, value) in metrics {
+            histories
+                .entry((type_of_work.to_string(), metric))
+                .or_default()
+                .push((year, value));
+        }
+    }
+
+    for history in histories.values_mut() {
+        history.sort_by_key(|(year, _)| *year);
+    }
+    histories
+}
+
+fn ask_backtest() -> bool {
+    loop {
+        println!("Forecast Mode");
+        println!("[1] Forecast next FundingYear");
+        println!("[2] Backtest (hold out the last year)");
+
+        print!("\nEnter Choice: ");
+        io::stdout().flush().unwrap();
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        println!();
+
+        match input.trim() {
+            "1" => return false,
+            "2" => return true,
+            _ => println!("Invalid choice, try again.\n"),
+        }
+    }
+}
+
+fn forecast_rows(
+    histories: &BTreeMap<(String, &'static str), History>,
+    alpha: f64,
+) -> Vec<ForecastReportRow> {
+    let mut rows = Vec::new();
+
+    for ((type_of_work, metric), history) in histories {
+        let target_year = history.last().unwrap().0 + 1;
+
+        for method in Method::ALL {
+            let result = forecast(method, history, target_year, alpha);
+            let note = match result.interval {
+                Some(_) => "",
+                None => "history too short for an interval",
+            };
+            let bound = |value: f64| round2(clamp_metric(metric, value));
+
+            rows.push(ForecastReportRow {
+                type_of_work: type_of_work.clone(),
+                metric: metric.to_string(),
+                method: method.name().to_string(),
+                history_years: history.len(),
+                forecast_year: target_year,
+                forecast: round2(clamp_metric(metric, result.value)),
+                lower_95: result.interval.map(|(lo, _)| bound(lo)),
+                upper_95: result.interval.map(|(_, hi)| bound(hi)),
+                note: note.to_string(),
+            });
+        }
+    }
+    rows
+}
+
+fn backtest_rows(
+    histories: &BTreeMap<(String, &'static str), History>,
+    alpha: f64,
+) -> Vec<BacktestReportRow> {
+    let mut rows = Vec::new();
+
+    for ((type_of_work, metric), history) in histories {
+        // Need at least one year to fit on besides the holdout
+        let Some(((holdout_year, actual), fit)) = history.split_last() else {
+            continue;
+        };
+        if fit.is_empty() {
+            continue;
+        }
+        let fit: History = fit.to_vec();
+
+        for method in Method::ALL {
+            let result = forecast(method, &fit, *holdout_year, alpha);
+            let predicted = clamp_metric(metric, result.value);
+            let abs_error = (predicted - actual).abs();
+            let pct_error = (*actual != 0.0).then(|| abs_error / actual.abs() * 100.0);
+            let within_interval = result
+                .interval
+                .is_some_and(|(lo, hi)| (lo..=hi).contains(actual));
+
+            rows.push(BacktestReportRow {
+                type_of_work: type_of_work.clone(),
+                metric: metric.to_string(),
+                method: method.name().to_string(),
+                holdout_year: *holdout_year,
+                forecast: round2(predicted),
+                actual: round2(*actual),
+                abs_error: round2(abs_error),
+                pct_error: pct_error.map(round2),
+                within_interval,
+            });
+        }
+    }
+    rows
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+pub fn generate_report(data: &DataSet, config: &Config) {
+    let backtest = ask_backtest();
+
+    println!("Generating Next-Year Forecast...\n");
+
+    let histories = histories(data);
+
+    if backtest {
+        let rows = backtest_rows(&histories, config.forecast.smoothing_alpha);
+
+        let table = Table::new(rows.clone());
+        println!("{table}");
+
+        for method in Method::ALL {
+            let scored: Vec<&BacktestReportRow> =
+                rows.iter().filter(|r| r.method == method.name()).collect();
+            let errors: Vec<f64> = scored.iter().filter_map(|r| r.pct_error).collect();
+            let skipped = scored.len() - errors.len();
+            if !errors.is_empty() {
+                println!(
+                    "{}: mean absolute percentage error {:.2}% (all metrics, {} points with an actual of 0 skipped)",
+                    method.name(),
+                    errors.iter().sum::<f64>() / errors.len() as f64,
+                    skipped
+                );
+            }
+        }
+        println!();
+
+        export_csv("report10_backtest.csv", &rows, data);
+
+        println!("Full table exported to report10_backtest.csv\n");
+    } else {
+        let rows = forecast_rows(&histories, config.forecast.smoothing_alpha);
+
+        let table = Table::new(rows.clone());
+        println!("{table}");
+
+        export_csv("report10_forecast.csv", &rows, data);
+
+        println!("Full table exported to report10_forecast.csv\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn linear_trend_extends_the_line() {
+        let forecast = linear_trend(&vec![(2020, 10.0), (2021, 20.0), (2022, 30.0)], 2024);
+        assert_close(forecast.value, 50.0);
+        // A perfect fit leaves no residual error, so the interval collapses on the forecast
+        let (lower, upper) = forecast.interval.unwrap();
+        assert_close(lower, 50.0);
+        assert_close(upper, 50.0);
+
+        // Two years fit a line but leave no degrees of freedom for an interval
+        let forecast = linear_trend(&vec![(2021, 20.0), (2022, 30.0)], 2023);
+        assert_close(forecast.value, 40.0);
+        assert!(forecast.interval.is_none());
+
+        // One year has no trend: the forecast is that year's value
+        let forecast = linear_trend(&vec![(2022, 30.0)], 2023);
+        assert_close(forecast.value, 30.0);
+        assert!(forecast.interval.is_none());
+    }
+
+    #[test]
+    fn exponential_smoothing_weights_recent_years() {
+        // Level 10, then 0.5 * 20 + 0.5 * 10 after one step with an error of 10
+        let forecast = exponential_smoothing(&vec![(2021, 10.0), (2022, 20.0)], 0.5);
+        assert_close(forecast.value, 15.0);
+        let (lower, upper) = forecast.interval.unwrap();
+        assert_close(lower, 15.0 - 19.6);
+        assert_close(upper, 15.0 + 19.6);
+
+        let forecast = exponential_smoothing(&vec![(2022, 20.0)], 0.5);
+        assert_close(forecast.value, 20.0);
+        assert!(forecast.interval.is_none());
+    }
+}
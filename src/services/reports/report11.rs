@@ -0,0 +1,263 @@
+use crate::services::config::{
+    Config, EfficiencyConfig, EfficiencyFormula, ReliabilityConfig, SensitivityConfig,
+};
+use crate::services::export::{display_option, export_csv};
+use crate::services::loader::DataSet;
+use crate::services::reports::{report1, report2};
+use crate::services::stats::{kendall_tau, spearman};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use tabled::{Table, Tabled};
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct SensitivityRow {
+    pub ranking: String,
+    pub scenario: String,
+    pub items: usize,
+    pub kendall_tau: f64,
+    pub spearman: f64,
+    /// Items joining or leaving the tracked set (High Risk); blank for regions.
+    #[tabled(display_with = "display_option")]
+    pub entered: Option<usize>,
+    #[tabled(display_with = "display_option")]
+    pub left: Option<usize>,
+    /// Items whose rank differs from the baseline, and the largest move in places.
+    pub rank_changes: usize,
+    pub max_rank_shift: usize,
+}
+
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct RankFlipRow {
+    pub ranking: String,
+    pub scenario: String,
+    pub name: String,
+    pub change: String,
+}
+
+/// Scores of one scenario, plus the name and members of the set whose membership is tracked
+/// (High Risk contractors). Regions have no such set; their rank changes are listed instead.
+struct Ranking {
+    scores: HashMap<String, f64>,
+    tracked: Option<(&'static str, BTreeSet<String>)>,
+}
+
+const CONTRACTORS: &str = "Contractor reliability";
+const REGIONS: &str = "Region efficiency";
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+fn round4(value: f64) -> f64 {
+    (value * 10000.0).round() / 10000.0
+}
+
+fn contractor_ranking(data: &DataSet, config: &ReliabilityConfig) -> Ranking {
+    let rows = report2::contractor_rows(data, &data.projects, config);
+    Ranking {
+        scores: rows
+            .iter()
+            .map(|r| (r.contractor.clone(), r.reliability_index))
+            .collect(),
+        tracked: Some((
+            "High Risk",
+            rows.iter()
+                .filter(|r| r.risk_flag == "High Risk")
+                .map(|r| r.contractor.clone())
+                .collect(),
+        )),
+    }
+}
+
+fn region_ranking(data: &DataSet, config: &EfficiencyConfig) -> Ranking {
+    let rows = report1::efficiency_rows(data, &data.projects, config);
+    Ranking {
+        scores: rows
+            .iter()
+            .map(|r| (r.region.clone(), r.efficiency_score))
+            .collect(),
+        tracked: None,
+    }
+}
+
+fn formula_label(config: &EfficiencyConfig) -> String {
+    let weights = format!(
+        "weights {}/{}/{}",
+        config.savings_weight, config.on_time_weight, config.delay_weight
+    );
+    match config.formula {
+        EfficiencyFormula::Ratio => "ratio".to_string(),
+        EfficiencyFormula::Zscore => format!("zscore, {weights}"),
+        EfficiencyFormula::Rank => format!("rank, {weights}"),
+    }
+}
+
+/// 1-based rank of each name by score, highest first (ties broken by name).
+fn ranks(names: &[&String], scores: &[f64]) -> HashMap<String, usize> {
+    let mut order: Vec<usize> = (0..names.len()).collect();
+    order.sort_by(|a, b| {
+        scores[*b]
+            .total_cmp(&scores[*a])
+            .then(names[*a].cmp(names[*b]))
+    });
+    order
+        .into_iter()
+        .enumerate()
+        .map(|(rank, i)| (names[i].clone(), rank + 1))
+        .collect()
+}
+
+/// Compares a scenario with the baseline over the items both rank.
+fn compare(
+    ranking: &str,
+    scenario: String,
+    baseline: &Ranking,
+    current: &Ranking,
+    flips: &mut Vec<RankFlipRow>,
+) -> SensitivityRow {
+    let mut names: Vec<&String> = baseline
+        .scores
+        .keys()
+        .filter(|name| current.scores.contains_key(*name))
+        .collect();
+    names.sort();
+
+    let before: Vec<f64> = names.iter().map(|n| baseline.scores[*n]).collect();
+    let after: Vec<f64> = names.iter().map(|n| current.scores[*n]).collect();
+
+    let (rank_before, rank_after) = (ranks(&names, &before), ranks(&names, &after));
+    let moved: Vec<(&String, usize, usize)> = names
+        .iter()
+        .map(|n| (*n, rank_before[*n], rank_after[*n]))
+        .filter(|(_, a, b)| a != b)
+        .collect();
+
+    let mut counts = None;
+    if let (Some((set_name, before_set)), Some((_, after_set))) =
+        (&baseline.tracked, &current.tracked)
+    {
+        let entered: Vec<&String> = after_set.difference(before_set).collect();
+        let left: Vec<&String> = before_set.difference(after_set).collect();
+
+        for (names, verb) in [(&entered, "entered"), (&left, "left")] {
+            for name in names {
+                flips.push(RankFlipRow {
+                    ranking: ranking.to_string(),
+                    scenario: scenario.clone(),
+                    name: name.to_string(),
+                    change: format!("{verb} {set_name}"),
+                });
+            }
+        }
+        counts = Some((entered.len(), left.len()));
+    } else {
+        for (name, from, to) in &moved {
+            flips.push(RankFlipRow {
+                ranking: ranking.to_string(),
+                scenario: scenario.clone(),
+                name: name.to_string(),
+                change: format!("rank {from} -> {to}"),
+            });
+        }
+    }
+
+    SensitivityRow {
+        ranking: ranking.to_string(),
+        scenario,
+        items: names.len(),
+        kendall_tau: round4(kendall_tau(&before, &after)),
+        spearman: round4(spearman(&before, &after)),
+        entered: counts.map(|c| c.0),
+        left: counts.map(|c| c.1),
+        rank_changes: moved.len(),
+        max_rank_shift: moved
+            .iter()
+            .map(|(_, a, b)| a.abs_diff(*b))
+            .max()
+            .unwrap_or(0),
+    }
+}
+
+fn efficiency_scenarios(sweep: &SensitivityConfig) -> Vec<EfficiencyConfig> {
+    let mut scenarios = vec![EfficiencyConfig {
+        formula: EfficiencyFormula::Ratio,
+        ..EfficiencyConfig::default()
+    }];
+    for formula in [EfficiencyFormula::Zscore, EfficiencyFormula::Rank] {
+        for [savings, on_time, delay] in &sweep.weight_sets {
+            scenarios.push(EfficiencyConfig {
+                formula,
+                savings_weight: *savings,
+                on_time_weight: *on_time,
+                delay_weight: *delay,
+            });
+        }
+    }
+    scenarios
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+pub fn generate_report(data: &DataSet, config: &Config) {
+    println!("Generating Ranking Sensitivity Analysis...\n");
+
+    let sweep = &config.sensitivity;
+    let mut rows: Vec<SensitivityRow> = Vec::new();
+    let mut flips: Vec<RankFlipRow> = Vec::new();
+
+    // Report 2: reliability index and High Risk flag
+    let baseline = contractor_ranking(data, &config.reliability);
+    for denominator in &sweep.delay_denominators {
+        for cutoff in &sweep.high_risk_cutoffs {
+            let scenario = ReliabilityConfig {
+                delay_denominator_days: *denominator,
+                high_risk_cutoff: *cutoff,
+            };
+            rows.push(compare(
+                CONTRACTORS,
+                format!("denominator {denominator} days, cutoff {cutoff}"),
+                &baseline,
+                &contractor_ranking(data, &scenario),
+                &mut flips,
+            ));
+        }
+    }
+
+    // Report 1: efficiency score formula and weights
+    report1::warn_without_targets(data);
+    let baseline = region_ranking(data, &config.efficiency);
+    for scenario in efficiency_scenarios(sweep) {
+        rows.push(compare(
+            REGIONS,
+            formula_label(&scenario),
+            &baseline,
+            &region_ranking(data, &scenario),
+            &mut flips,
+        ));
+    }
+
+    println!(
+        "Baselines: denominator {} days, cutoff {}; efficiency {}\n",
+        config.reliability.delay_denominator_days,
+        config.reliability.high_risk_cutoff,
+        formula_label(&config.efficiency)
+    );
+
+    let table = Table::new(rows.clone());
+    println!("{table}");
+
+    if flips.is_empty() {
+        println!("No contractor changes group and no region changes rank in any scenario\n");
+    } else {
+        let table = Table::new(flips.clone());
+        println!("{table}");
+    }
+
+    export_csv("report11_sensitivity.csv", &rows, data);
+    export_csv("report11_rank_flips.csv", &flips, data);
+
+    println!("Full tables exported to report11_sensitivity.csv and report11_rank_flips.csv\n");
+}
@@ -0,0 +1,353 @@
+use crate::services::export::export_csv;
+use crate::services::loader::DataSet;
+use crate::services::project::{Delay, Project};
+use crate::services::stats::median;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use tabled::{Table, Tabled};
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct ContractorProjectRow {
+    pub row_number: usize,
+    pub project_id: String,
+    pub funding_year: String,
+    pub region: String,
+    pub province: String,
+    pub type_of_work: String,
+    pub contract_cost: f64,
+    pub savings: f64,
+    pub delay: String,
+    pub status: String,
+}
+
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct FootprintRow {
+    pub dimension: String,
+    pub group: String,
+    pub num_projects: usize,
+    pub total_cost: f64,
+    pub cost_share_pct: f64,
+}
+
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct YearTrendRow {
+    pub funding_year: String,
+    pub num_projects: usize,
+    pub total_cost: f64,
+    pub total_savings: f64,
+    pub avg_delay: f64,
+    pub incomplete_excluded: usize,
+    pub delay_missing: usize,
+}
+
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct PeerComparisonRow {
+    pub type_of_work: String,
+    pub metric: String,
+    pub contractor_projects: usize,
+    pub contractor_median: f64,
+    pub peer_projects: usize,
+    pub peer_median: f64,
+    pub difference: f64,
+}
+
+// Names scoring below this are not considered a match
+const MIN_MATCH_SCORE: f64 = 0.6;
+/// Shorter names are too likely to turn up inside unrelated ones to count as a substring match.
+const MIN_SUBSTRING_LEN: usize = 3;
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Uppercase, punctuation to spaces, runs of spaces collapsed.
+fn normalise(name: &str) -> String {
+    name.to_uppercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// 1 for the same normalised name, 0.9 when one contains the other (of at least
+/// `MIN_SUBSTRING_LEN` characters), else edit-distance similarity.
+fn match_score(query: &str, name: &str) -> f64 {
+    let (query, name) = (normalise(query), normalise(name));
+    let contains = |outer: &str, inner: &str| {
+        inner.chars().count() >= MIN_SUBSTRING_LEN && outer.contains(inner)
+    };
+    if query == name {
+        1.0
+    } else if contains(&name, &query) || contains(&query, &name) {
+        0.9
+    } else {
+        let longest = query.chars().count().max(name.chars().count()).max(1);
+        1.0 - levenshtein(&query, &name) as f64 / longest as f64
+    }
+}
+
+/// Contractors matching `query`, best first (ties go to the one with more projects).
+fn find_matches(projects: &[Project], query: &str) -> Vec<(String, f64)> {
+    // Joint ventures and their member firms can both be looked up
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for project in projects {
+        let mut names: Vec<&str> = project.members.iter().map(|m| m.name.as_str()).collect();
+        names.push(&project.contractor);
+        names.dedup();
+        for name in names {
+            *counts.entry(name).or_default() += 1;
+        }
+    }
+
+    let mut matches: Vec<(String, f64, usize)> = counts
+        .into_iter()
+        .map(|(name, count)| (name.to_string(), match_score(query, name), count))
+        .filter(|(_, score, _)| *score >= MIN_MATCH_SCORE)
+        .collect();
+    matches.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap().then(b.2.cmp(&a.2)));
+
+    matches
+        .into_iter()
+        .map(|(name, score, _)| (name, score))
+        .collect()
+}
+
+fn display_delay(delay: Delay) -> String {
+    match delay {
+        Delay::Observed(days) => days.to_string(),
+        Delay::Censored(days) => format!(">= {days}"),
+        Delay::Excluded | Delay::Missing => String::new(),
+    }
+}
+
+fn footprint_rows<F>(projects: &[&Project], dimension: &str, key: F) -> Vec<FootprintRow>
+where
+    F: Fn(&Project) -> &str,
+{
+    let total: f64 = projects.iter().map(|p| p.contract_cost).sum();
+    let mut groups: HashMap<&str, (usize, f64)> = HashMap::new();
+    for project in projects {
+        let entry = groups.entry(key(project)).or_default();
+        entry.0 += 1;
+        entry.1 += project.contract_cost;
+    }
+
+    let mut rows: Vec<FootprintRow> = groups
+        .into_iter()
+        .map(|(group, (count, cost))| FootprintRow {
+            dimension: dimension.to_string(),
+            group: group.to_string(),
+            num_projects: count,
+            total_cost: round2(cost),
+            cost_share_pct: if total > 0.0 {
+                round2(cost / total * 100.0)
+            } else {
+                0.0
+            },
+        })
+        .collect();
+    rows.sort_by(|a, b| b.total_cost.partial_cmp(&a.total_cost).unwrap());
+    rows
+}
+
+fn trend_rows(projects: &[&Project], data: &DataSet) -> Vec<YearTrendRow> {
+    let mut years: BTreeMap<&str, Vec<&Project>> = BTreeMap::new();
+    for project in projects {
+        years
+            .entry(&project.funding_year)
+            .or_default()
+            .push(project);
+    }
+
+    years
+        .into_iter()
+        .map(|(year, projects)| {
+            let delays: Vec<Delay> = projects.iter().map(|p| p.delay(&data.delay)).collect();
+            YearTrendRow {
+                funding_year: year.to_string(),
+                num_projects: projects.len(),
+                total_cost: round2(projects.iter().map(|p| p.contract_cost).sum()),
+                total_savings: round2(projects.iter().map(|p| p.savings()).sum()),
+                avg_delay: round2(Delay::mean_observed(delays.iter()).unwrap_or(0.0)),
+                incomplete_excluded: delays.iter().filter(|d| **d == Delay::Excluded).count(),
+                delay_missing: delays.iter().filter(|d| **d == Delay::Missing).count(),
+            }
+        })
+        .collect()
+}
+
+/// Per-project medians of the contractor against every other contractor, within each TypeOfWork
+/// the contractor works in.
+fn peer_rows(contractor: &str, data: &DataSet) -> Vec<PeerComparisonRow> {
+    type Metric = (&'static str, fn(&Project, &DataSet) -> Option<f64>);
+    let metrics: [Metric; 3] = [
+        ("savings", |p, _| Some(p.savings())),
+        ("savings_pct", |p, _| {
+            (p.approved_budget > 0.0).then(|| p.savings() / p.approved_budget * 100.0)
+        }),
+        ("delay", |p, d| {
+            p.delay(&d.delay).observed().map(|v| v as f64)
+        }),
+    ];
+
+    let mut types: Vec<&str> = data
+        .projects
+        .iter()
+        .filter(|p| p.involves(contractor))
+        .map(|p| p.type_of_work.as_str())
+        .collect();
+    types.sort();
+    types.dedup();
+
+    let mut rows = Vec::new();
+    for work_type in types {
+        for (metric, value) in &metrics {
+            let (mut own, mut peers) = (Vec::new(), Vec::new());
+            for project in data.projects.iter().filter(|p| p.type_of_work == work_type) {
+                if let Some(v) = value(project, data) {
+                    if project.involves(contractor) {
+                        own.push(v);
+                    } else {
+                        peers.push(v);
+                    }
+                }
+            }
+            if own.is_empty() || peers.is_empty() {
+                continue;
+            }
+
+            let (own_median, peer_median) = (median(&own), median(&peers));
+            rows.push(PeerComparisonRow {
+                type_of_work: work_type.to_string(),
+                metric: metric.to_string(),
+                contractor_projects: own.len(),
+                contractor_median: round2(own_median),
+                peer_projects: peers.len(),
+                peer_median: round2(peer_median),
+                difference: round2(own_median - peer_median),
+            });
+        }
+    }
+    rows
+}
+
+fn ask_name() -> String {
+    loop {
+        print!("Contractor name: ");
+        io::stdout().flush().unwrap();
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        println!();
+
+        if !normalise(&input).is_empty() {
+            return input.trim().to_string();
+        }
+        if !input.trim().is_empty() {
+            println!("The name needs at least one letter or digit\n");
+        }
+    }
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+/// Menu entry: asks for the contractor, then runs the profile.
+pub fn prompt_report(data: &DataSet) {
+    let query = ask_name();
+    generate_report(data, &query);
+}
+
+pub fn generate_report(data: &DataSet, query: &str) {
+    println!("Generating Contractor Profile...\n");
+
+    if normalise(query).is_empty() {
+        eprintln!("Error: contractor name \"{query}\" has no letters or digits\n");
+        return;
+    }
+
+    let matches = find_matches(&data.projects, query);
+    let Some((contractor, _)) = matches.first() else {
+        println!("No contractor matches \"{query}\"\n");
+        return;
+    };
+
+    println!("Contractor: {contractor}");
+    if matches.len() > 1 {
+        println!("Other close matches:");
+        for (name, score) in matches.iter().skip(1).take(5) {
+            println!("  {name} (match {:.0}%)", score * 100.0);
+        }
+    }
+    println!();
+
+    let projects: Vec<&Project> = data
+        .projects
+        .iter()
+        .filter(|p| p.involves(contractor))
+        .collect();
+
+    let project_rows: Vec<ContractorProjectRow> = projects
+        .iter()
+        .map(|p| ContractorProjectRow {
+            row_number: p.row_number,
+            project_id: p.project_id.clone(),
+            funding_year: p.funding_year.clone(),
+            region: p.region.clone(),
+            province: p.province.clone(),
+            type_of_work: p.type_of_work.clone(),
+            contract_cost: round2(p.contract_cost),
+            savings: round2(p.savings()),
+            delay: display_delay(p.delay(&data.delay)),
+            status: p.status(data.delay.reference_date).name().to_string(),
+        })
+        .collect();
+
+    let mut footprint = footprint_rows(&projects, "Region", |p| &p.region);
+    footprint.extend(footprint_rows(&projects, "Province", |p| &p.province));
+    footprint.extend(footprint_rows(&projects, "TypeOfWork", |p| &p.type_of_work));
+
+    let trend = trend_rows(&projects, data);
+    let peers = peer_rows(contractor, data);
+
+    println!("Projects");
+    println!("{}", Table::new(project_rows.clone()));
+    println!("Footprint");
+    println!("{}", Table::new(footprint.clone()));
+    println!("Year-by-Year Trend");
+    println!("{}", Table::new(trend.clone()));
+    println!("Compared with Peers (medians per project)");
+    println!("{}", Table::new(peers.clone()));
+
+    export_csv("report12_contractor_projects.csv", &project_rows, data);
+    export_csv("report12_contractor_footprint.csv", &footprint, data);
+    export_csv("report12_contractor_trend.csv", &trend, data);
+    export_csv("report12_contractor_peers.csv", &peers, data);
+
+    println!(
+        "Full tables exported to report12_contractor_projects.csv, report12_contractor_footprint.csv, report12_contractor_trend.csv and report12_contractor_peers.csv\n"
+    );
+}
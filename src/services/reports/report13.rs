@@ -0,0 +1,307 @@
+use crate::services::export::{export_csv, export_text};
+use crate::services::loader::DataSet;
+use crate::services::project::Project;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use tabled::{Table, Tabled};
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct FirmRow {
+    pub firm: String,
+    pub component: usize,
+    pub degree: usize,
+    pub solo_projects: usize,
+    pub jv_projects: usize,
+    pub total_cost: f64,
+    pub partners: String,
+}
+
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct ClusterRow {
+    pub component: usize,
+    pub num_firms: usize,
+    pub num_projects: usize,
+    pub combined_cost: f64,
+    pub firms: String,
+}
+
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct PartnershipRow {
+    pub firm_a: String,
+    pub firm_b: String,
+    pub joint_projects: usize,
+    pub joint_cost: f64,
+}
+
+#[derive(Default)]
+struct FirmTotals {
+    solo_projects: usize,
+    jv_projects: usize,
+    total_cost: f64,
+}
+
+// Clusters printed to the console; the export has all of them
+const TOP_CLUSTERS: usize = 10;
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// A firm's share of a project's cost as credited by `jv_attribution`; an equal share when
+/// joint ventures are not split and the firm is not credited on its own.
+fn credited_share(project: &Project, firm: &str) -> f64 {
+    project
+        .members
+        .iter()
+        .find(|m| m.name == firm)
+        .map_or(1.0 / project.firms.len() as f64, |m| m.share)
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Connected components, numbered later by combined cost; firms map to a provisional index.
+fn components(
+    firms: &BTreeMap<&str, FirmTotals>,
+    partners: &BTreeMap<&str, BTreeSet<&str>>,
+) -> HashMap<String, usize> {
+    let mut component: HashMap<String, usize> = HashMap::new();
+    let mut next = 0;
+
+    for firm in firms.keys() {
+        if component.contains_key(*firm) {
+            continue;
+        }
+        let mut stack = vec![*firm];
+        while let Some(current) = stack.pop() {
+            if component.insert(current.to_string(), next).is_some() {
+                continue;
+            }
+            if let Some(neighbours) = partners.get(current) {
+                stack.extend(neighbours.iter().filter(|n| !component.contains_key(**n)));
+            }
+        }
+        next += 1;
+    }
+    component
+}
+
+fn graphml(firms: &[FirmRow], edges: &[PartnershipRow]) -> String {
+    let ids: HashMap<&str, usize> = firms
+        .iter()
+        .enumerate()
+        .map(|(i, f)| (f.firm.as_str(), i))
+        .collect();
+
+    let mut out = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="label" for="node" attr.name="label" attr.type="string"/>
+  <key id="component" for="node" attr.name="component" attr.type="int"/>
+  <key id="total_cost" for="node" attr.name="total_cost" attr.type="double"/>
+  <key id="joint_projects" for="edge" attr.name="joint_projects" attr.type="int"/>
+  <key id="joint_cost" for="edge" attr.name="joint_cost" attr.type="double"/>
+  <graph id="jv_network" edgedefault="undirected">
+"#,
+    );
+    for (i, firm) in firms.iter().enumerate() {
+        out.push_str(&format!(
+            "    <node id=\"n{i}\"><data key=\"label\">{}</data><data key=\"component\">{}</data><data key=\"total_cost\">{}</data></node>\n",
+            xml_escape(&firm.firm),
+            firm.component,
+            firm.total_cost
+        ));
+    }
+    for edge in edges {
+        out.push_str(&format!(
+            "    <edge source=\"n{}\" target=\"n{}\"><data key=\"joint_projects\">{}</data><data key=\"joint_cost\">{}</data></edge>\n",
+            ids[edge.firm_a.as_str()],
+            ids[edge.firm_b.as_str()],
+            edge.joint_projects,
+            edge.joint_cost
+        ));
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+fn dot(firms: &[FirmRow], edges: &[PartnershipRow]) -> String {
+    let mut out = String::from("graph jv_network {\n");
+    for firm in firms {
+        out.push_str(&format!(
+            "  \"{}\" [component={}, total_cost={}];\n",
+            dot_escape(&firm.firm),
+            firm.component,
+            firm.total_cost
+        ));
+    }
+    for edge in edges {
+        out.push_str(&format!(
+            "  \"{}\" -- \"{}\" [weight={}, label=\"{}\"];\n",
+            dot_escape(&edge.firm_a),
+            dot_escape(&edge.firm_b),
+            edge.joint_projects,
+            edge.joint_projects
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+pub fn generate_report(data: &DataSet) {
+    println!("Generating Joint Venture Network Report...\n");
+
+    let mut firms: BTreeMap<&str, FirmTotals> = BTreeMap::new();
+    let mut partners: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    let mut edges: BTreeMap<(&str, &str), (usize, f64)> = BTreeMap::new();
+
+    // Partnerships come from every member firm; only `total_cost` follows `jv_attribution`
+    for project in &data.projects {
+        let members: Vec<&str> = project.firms.iter().map(String::as_str).collect();
+        let joint = members.len() > 1;
+
+        for member in &members {
+            let totals = firms.entry(member).or_default();
+            if joint {
+                totals.jv_projects += 1;
+            } else {
+                totals.solo_projects += 1;
+            }
+            totals.total_cost += project.contract_cost * credited_share(project, member);
+        }
+
+        for (i, a) in members.iter().enumerate() {
+            for b in &members[i + 1..] {
+                let pair = if a < b { (*a, *b) } else { (*b, *a) };
+                let edge = edges.entry(pair).or_default();
+                edge.0 += 1;
+                edge.1 += project.contract_cost;
+                partners.entry(a).or_default().insert(b);
+                partners.entry(b).or_default().insert(a);
+            }
+        }
+    }
+
+    // Number components 1.. by combined cost, counting each project once
+    let provisional = components(&firms, &partners);
+    let mut members_of: BTreeMap<usize, Vec<&str>> = BTreeMap::new();
+    for firm in firms.keys() {
+        members_of.entry(provisional[*firm]).or_default().push(firm);
+    }
+    let mut totals_of: HashMap<usize, (usize, f64)> = HashMap::new();
+    for project in &data.projects {
+        let first = project.firms[0].as_str();
+        let totals = totals_of.entry(provisional[first]).or_default();
+        totals.0 += 1;
+        totals.1 += project.contract_cost;
+    }
+
+    let mut clusters: Vec<ClusterRow> = members_of
+        .iter()
+        .map(|(id, names)| ClusterRow {
+            component: *id,
+            num_firms: names.len(),
+            num_projects: totals_of[id].0,
+            combined_cost: round2(totals_of[id].1),
+            firms: names.join("; "),
+        })
+        .collect();
+    clusters.sort_by(|a, b| b.combined_cost.partial_cmp(&a.combined_cost).unwrap());
+
+    let mut renumber: HashMap<usize, usize> = HashMap::new();
+    for (i, cluster) in clusters.iter_mut().enumerate() {
+        renumber.insert(cluster.component, i + 1);
+        cluster.component = i + 1;
+    }
+
+    let mut firm_rows: Vec<FirmRow> = firms
+        .iter()
+        .map(|(firm, totals)| {
+            let linked = partners.get(firm);
+            FirmRow {
+                firm: firm.to_string(),
+                component: renumber[&provisional[*firm]],
+                degree: linked.map_or(0, |p| p.len()),
+                solo_projects: totals.solo_projects,
+                jv_projects: totals.jv_projects,
+                total_cost: round2(totals.total_cost),
+                partners: linked
+                    .map(|p| p.iter().copied().collect::<Vec<_>>().join("; "))
+                    .unwrap_or_default(),
+            }
+        })
+        .collect();
+    firm_rows.sort_by(|a, b| {
+        b.degree
+            .cmp(&a.degree)
+            .then(b.total_cost.partial_cmp(&a.total_cost).unwrap())
+    });
+
+    let partnerships: Vec<PartnershipRow> = edges
+        .iter()
+        .map(|((a, b), (count, cost))| PartnershipRow {
+            firm_a: a.to_string(),
+            firm_b: b.to_string(),
+            joint_projects: *count,
+            joint_cost: round2(*cost),
+        })
+        .collect();
+
+    let networked: Vec<ClusterRow> = clusters
+        .iter()
+        .filter(|c| c.num_firms > 1)
+        .cloned()
+        .collect();
+    println!(
+        "{} firms, {} partnerships, {} connected components ({} with more than one firm)\n",
+        firm_rows.len(),
+        partnerships.len(),
+        clusters.len(),
+        networked.len()
+    );
+
+    println!("Firms in Joint Ventures");
+    let linked: Vec<FirmRow> = firm_rows.iter().filter(|f| f.degree > 0).cloned().collect();
+    println!("{}", Table::new(linked));
+    println!("Largest Clusters by Combined ContractCost");
+    let top: Vec<ClusterRow> = networked.into_iter().take(TOP_CLUSTERS).collect();
+    println!("{}", Table::new(top));
+
+    export_csv("report13_jv_firms.csv", &firm_rows, data);
+    export_csv("report13_jv_clusters.csv", &clusters, data);
+    export_csv("report13_jv_partnerships.csv", &partnerships, data);
+    export_text(
+        "report13_jv_network.graphml",
+        &graphml(&firm_rows, &partnerships),
+        firm_rows.len(),
+        data,
+    );
+    export_text(
+        "report13_jv_network.dot",
+        &dot(&firm_rows, &partnerships),
+        firm_rows.len(),
+        data,
+    );
+
+    println!(
+        "Full tables exported to report13_jv_firms.csv, report13_jv_clusters.csv and report13_jv_partnerships.csv"
+    );
+    println!("Network exported to report13_jv_network.graphml and report13_jv_network.dot\n");
+}
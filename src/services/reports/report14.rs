@@ -0,0 +1,287 @@
+use crate::services::config::HotspotConfig;
+use crate::services::export::{display_option, export_csv};
+use crate::services::geo::{Coordinates, haversine_km};
+use crate::services::loader::DataSet;
+use crate::services::project::Project;
+use crate::services::stats::median;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use tabled::{Table, Tabled};
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct HotspotRow {
+    pub method: String,
+    pub cluster: String,
+    pub num_projects: usize,
+    pub center_latitude: f64,
+    pub center_longitude: f64,
+    pub extent_km: f64,
+    pub total_cost: f64,
+    pub dominant_contractor: String,
+    pub dominant_share_pct: f64,
+    #[tabled(display_with = "display_option")]
+    pub median_delay: Option<f64>,
+}
+
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct HotspotProjectRow {
+    pub method: String,
+    pub cluster: String,
+    pub row_number: usize,
+    pub project_id: String,
+    pub contractor: String,
+    pub type_of_work: String,
+    pub contract_cost: f64,
+}
+
+// Hotspots printed per method; the export has all of them
+const TOP_HOTSPOTS: usize = 15;
+const KM_PER_DEGREE: f64 = 111.32;
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn round5(value: f64) -> f64 {
+    (value * 100000.0).round() / 100000.0
+}
+
+/// Square cells of roughly `cell_km` a side; longitude steps widen away from the equator.
+fn grid_clusters<'a>(
+    points: &[(&'a Project, Coordinates)],
+    config: &HotspotConfig,
+) -> Vec<(String, Vec<&'a Project>)> {
+    let lat_step = config.grid_cell_km / KM_PER_DEGREE;
+    let mut cells: BTreeMap<(i64, i64), Vec<&Project>> = BTreeMap::new();
+
+    for (project, point) in points {
+        let row = (point.latitude / lat_step).floor() as i64;
+        let row_center = (row as f64 + 0.5) * lat_step;
+        let lon_step = config.grid_cell_km / (KM_PER_DEGREE * row_center.to_radians().cos());
+        let col = (point.longitude / lon_step).floor() as i64;
+        cells.entry((row, col)).or_default().push(project);
+    }
+
+    cells
+        .into_iter()
+        .filter(|(_, projects)| projects.len() >= config.min_projects)
+        .map(|((row, col), projects)| (format!("cell {row}:{col}"), projects))
+        .collect()
+}
+
+/// DBSCAN on great-circle distance; projects in no cluster are noise and left out.
+fn dbscan_clusters<'a>(
+    points: &[(&'a Project, Coordinates)],
+    config: &HotspotConfig,
+) -> Vec<(String, Vec<&'a Project>)> {
+    let neighbours = |i: usize| -> Vec<usize> {
+        (0..points.len())
+            .filter(|j| haversine_km(points[i].1, points[*j].1) <= config.radius_km)
+            .collect()
+    };
+
+    let mut labels: Vec<Option<usize>> = vec![None; points.len()];
+    let mut visited = vec![false; points.len()];
+    let mut next = 0;
+
+    for i in 0..points.len() {
+        if visited[i] {
+            continue;
+        }
+        visited[i] = true;
+        let seeds = neighbours(i);
+        if seeds.len() < config.min_projects {
+            continue;
+        }
+
+        labels[i] = Some(next);
+        let mut queue: VecDeque<usize> = seeds.into();
+        while let Some(j) = queue.pop_front() {
+            if !visited[j] {
+                visited[j] = true;
+                let reach = neighbours(j);
+                if reach.len() >= config.min_projects {
+                    queue.extend(reach);
+                }
+            }
+            if labels[j].is_none() {
+                labels[j] = Some(next);
+            }
+        }
+        next += 1;
+    }
+
+    let mut clusters: BTreeMap<usize, Vec<&Project>> = BTreeMap::new();
+    for (label, (project, _)) in labels.iter().zip(points) {
+        if let Some(id) = label {
+            clusters.entry(*id).or_default().push(project);
+        }
+    }
+    clusters
+        .into_iter()
+        .map(|(id, projects)| (format!("cluster {}", id + 1), projects))
+        .collect()
+}
+
+fn hotspot_row(method: &str, cluster: &str, projects: &[&Project], data: &DataSet) -> HotspotRow {
+    let points: Vec<Coordinates> = projects.iter().filter_map(|p| p.coordinates).collect();
+    let n = points.len() as f64;
+    let center = Coordinates {
+        latitude: points.iter().map(|c| c.latitude).sum::<f64>() / n,
+        longitude: points.iter().map(|c| c.longitude).sum::<f64>() / n,
+    };
+    let extent_km = points
+        .iter()
+        .map(|c| haversine_km(center, *c))
+        .fold(0.0, f64::max);
+
+    let total_cost: f64 = projects.iter().map(|p| p.contract_cost).sum();
+    let mut by_contractor: HashMap<&str, f64> = HashMap::new();
+    for project in projects {
+        *by_contractor.entry(&project.contractor).or_default() += project.contract_cost;
+    }
+    let (dominant, dominant_cost) = by_contractor
+        .into_iter()
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap().then(b.0.cmp(a.0)))
+        .unwrap();
+
+    let delays: Vec<f64> = projects
+        .iter()
+        .filter_map(|p| p.delay(&data.delay).observed())
+        .map(|d| d as f64)
+        .collect();
+
+    HotspotRow {
+        method: method.to_string(),
+        cluster: cluster.to_string(),
+        num_projects: projects.len(),
+        center_latitude: round5(center.latitude),
+        center_longitude: round5(center.longitude),
+        extent_km: round2(extent_km),
+        total_cost: round2(total_cost),
+        dominant_contractor: dominant.to_string(),
+        dominant_share_pct: if total_cost > 0.0 {
+            round2(dominant_cost / total_cost * 100.0)
+        } else {
+            0.0
+        },
+        median_delay: (!delays.is_empty()).then(|| round2(median(&delays))),
+    }
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+pub fn generate_report(data: &DataSet, config: &HotspotConfig) {
+    println!("Generating Project Hotspot Report...\n");
+
+    let points: Vec<(&Project, Coordinates)> = data
+        .projects
+        .iter()
+        .filter_map(|p| p.coordinates.map(|c| (p, c)))
+        .collect();
+    println!(
+        "{} projects with coordinates; grid cells of {} km, DBSCAN radius {} km, at least {} projects per hotspot\n",
+        points.len(),
+        config.grid_cell_km,
+        config.radius_km,
+        config.min_projects
+    );
+
+    let mut rows: Vec<HotspotRow> = Vec::new();
+    let mut members: Vec<HotspotProjectRow> = Vec::new();
+
+    let methods = [
+        ("Grid", grid_clusters(&points, config)),
+        ("DBSCAN", dbscan_clusters(&points, config)),
+    ];
+    for (method, clusters) in &methods {
+        let mut method_rows: Vec<HotspotRow> = clusters
+            .iter()
+            .map(|(cluster, projects)| hotspot_row(method, cluster, projects, data))
+            .collect();
+        method_rows.sort_by(|a, b| {
+            b.num_projects
+                .cmp(&a.num_projects)
+                .then(b.total_cost.partial_cmp(&a.total_cost).unwrap())
+        });
+
+        println!("{method} Hotspots");
+        if method_rows.is_empty() {
+            println!("No hotspots found\n");
+        } else {
+            let top: Vec<HotspotRow> = method_rows.iter().take(TOP_HOTSPOTS).cloned().collect();
+            println!("{}", Table::new(top));
+        }
+        rows.extend(method_rows);
+
+        for (cluster, projects) in clusters {
+            members.extend(projects.iter().map(|p| HotspotProjectRow {
+                method: method.to_string(),
+                cluster: cluster.clone(),
+                row_number: p.row_number,
+                project_id: p.project_id.clone(),
+                contractor: p.contractor.clone(),
+                type_of_work: p.type_of_work.clone(),
+                contract_cost: round2(p.contract_cost),
+            }));
+        }
+    }
+
+    export_csv("report14_hotspots.csv", &rows, data);
+    export_csv("report14_hotspot_projects.csv", &members, data);
+
+    println!("Full tables exported to report14_hotspots.csv and report14_hotspot_projects.csv\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::project::fixtures::project;
+
+    #[test]
+    fn dbscan_grows_clusters_through_core_points() {
+        // 0.001 degrees of latitude is about 0.11 km, 0.017 about 1.89 km
+        let latitudes = [15.0, 14.0, 14.001, 14.002, 14.019, 16.0, 16.001, 16.002];
+        let projects: Vec<Project> = (0..latitudes.len())
+            .map(|row| project(row, "Region A", "100"))
+            .collect();
+        let points: Vec<(&Project, Coordinates)> = projects
+            .iter()
+            .zip(latitudes)
+            .map(|(p, latitude)| {
+                let coordinates = Coordinates {
+                    latitude,
+                    longitude: 121.0,
+                };
+                (p, coordinates)
+            })
+            .collect();
+        let config = HotspotConfig {
+            radius_km: 2.0,
+            min_projects: 3,
+            ..Default::default()
+        };
+
+        let clusters: Vec<(String, Vec<usize>)> = dbscan_clusters(&points, &config)
+            .into_iter()
+            .map(|(name, members)| (name, members.iter().map(|p| p.row_number).collect()))
+            .collect();
+
+        // 15.0 is noise; 14.019 has too few neighbours to be a core point but is reached
+        // from 14.002, so it joins the first cluster as a border point
+        assert_eq!(
+            clusters,
+            vec![
+                ("cluster 1".to_string(), vec![1, 2, 3, 4]),
+                ("cluster 2".to_string(), vec![5, 6, 7]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use crate::services::export::{display_option, export_csv};
+use crate::services::loader::DataSet;
+use crate::services::project::Delay;
+use crate::services::reference::per_unit;
+use crate::services::stats::median;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use tabled::{Table, Tabled};
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct DistrictReportRow {
+    pub region: String,
+    pub province: String,
+    pub legislative_district: String,
+    pub num_projects: usize,
+    pub num_contractors: usize,
+    pub total_abc: f64,
+    pub median_savings: f64,
+    pub avg_delay: f64,
+    #[tabled(display_with = "display_option")]
+    pub abc_per_capita: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub abc_per_km2: Option<f64>,
+    /// Ongoing projects left out of `avg_delay` by the `exclude` policy.
+    pub incomplete_excluded: usize,
+    /// Projects without the dates needed for a delay, or with unknown status.
+    pub delay_missing: usize,
+}
+
+type DistrictGroups = HashMap<(String, String, String), Vec<(f64, f64, Delay, String)>>;
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+pub fn generate_report(data: &DataSet) {
+    println!("Generating Legislative District Report...\n");
+
+    if data
+        .projects
+        .iter()
+        .all(|p| p.legislative_district.is_empty())
+    {
+        println!("The source file has no LegislativeDistrict values\n");
+        return;
+    }
+
+    let reference = data.reference.districts.as_ref();
+    if reference.is_none() {
+        println!("No district reference table; per-capita and per-km2 columns left blank\n");
+    }
+
+    let mut groups: DistrictGroups = HashMap::new();
+
+    for project in &data.projects {
+        groups
+            .entry((
+                project.region.clone(),
+                project.province.clone(),
+                project.legislative_district.clone(),
+            ))
+            .or_default()
+            .push((
+                project.approved_budget,
+                project.savings(),
+                project.delay(&data.delay),
+                project.contractor.clone(),
+            ));
+    }
+
+    let mut rows: Vec<DistrictReportRow> = Vec::new();
+
+    for ((region, province, district), entries) in groups {
+        let total_abc: f64 = entries.iter().map(|e| e.0).sum();
+        let savings_list: Vec<f64> = entries.iter().map(|e| e.1).collect();
+        let contractors: HashSet<&str> = entries.iter().map(|e| e.3.as_str()).collect();
+        let known = reference.and_then(|r| r.get(&[&province, &district]));
+
+        rows.push(DistrictReportRow {
+            num_projects: entries.len(),
+            num_contractors: contractors.len(),
+            total_abc: round2(total_abc),
+            median_savings: round2(median(&savings_list)),
+            avg_delay: round2(Delay::mean_observed(entries.iter().map(|e| &e.2)).unwrap_or(0.0)),
+            abc_per_capita: per_unit(total_abc, known.and_then(|r| r.population)),
+            abc_per_km2: per_unit(total_abc, known.and_then(|r| r.land_area_km2)),
+            incomplete_excluded: entries.iter().filter(|e| e.2 == Delay::Excluded).count(),
+            delay_missing: entries.iter().filter(|e| e.2 == Delay::Missing).count(),
+            region,
+            province,
+            legislative_district: district,
+        });
+    }
+
+    // Sort descending by total_abc
+    rows.sort_by(|a, b| b.total_abc.partial_cmp(&a.total_abc).unwrap());
+
+    let table = Table::new(rows.clone());
+    println!("{table}");
+
+    export_csv("report15_legislative_districts.csv", &rows, data);
+
+    println!("Full table exported to report15_legislative_districts.csv\n");
+}
@@ -0,0 +1,112 @@
+use crate::services::export::{display_option, export_csv};
+use crate::services::loader::DataSet;
+use crate::services::project::Project;
+use crate::services::reference::{ReferenceTable, per_unit};
+use serde::Serialize;
+use std::collections::HashMap;
+use tabled::{Table, Tabled};
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct SpendNormalisedRow {
+    pub level: String,
+    pub name: String,
+    pub num_projects: usize,
+    pub total_budget: f64,
+    #[tabled(display_with = "display_option")]
+    pub population: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub budget_per_capita: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub hazard_exposed_ha: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub budget_per_hazard_ha: Option<f64>,
+}
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// One row per group, highest budget per capita first; groups missing from the table come last.
+fn normalised_rows<F>(
+    projects: &[Project],
+    level: &str,
+    table: &ReferenceTable,
+    key: F,
+) -> Vec<SpendNormalisedRow>
+where
+    F: Fn(&Project) -> &str,
+{
+    let mut groups: HashMap<&str, (usize, f64)> = HashMap::new();
+    for project in projects {
+        let entry = groups.entry(key(project)).or_default();
+        entry.0 += 1;
+        entry.1 += project.approved_budget;
+    }
+
+    let mut rows: Vec<SpendNormalisedRow> = groups
+        .into_iter()
+        .map(|(name, (count, budget))| {
+            let reference = table.get(&[name]);
+            let population = reference.and_then(|r| r.population);
+            let exposed = reference.and_then(|r| r.hazard_exposed_ha());
+            SpendNormalisedRow {
+                level: level.to_string(),
+                name: name.to_string(),
+                num_projects: count,
+                total_budget: round2(budget),
+                population,
+                budget_per_capita: per_unit(budget, population),
+                hazard_exposed_ha: exposed.map(round2),
+                budget_per_hazard_ha: per_unit(budget, exposed),
+            }
+        })
+        .collect();
+
+    rows.sort_by(|a, b| {
+        b.budget_per_capita
+            .unwrap_or(f64::MIN)
+            .partial_cmp(&a.budget_per_capita.unwrap_or(f64::MIN))
+            .unwrap()
+            .then(b.total_budget.partial_cmp(&a.total_budget).unwrap())
+    });
+    rows
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+pub fn generate_report(data: &DataSet) {
+    println!("Generating Spend per Capita and Flood Exposure Report...\n");
+
+    type Key = fn(&Project) -> &str;
+    let levels: [(&str, &Option<ReferenceTable>, Key); 2] = [
+        ("Region", &data.reference.regions, |p| &p.region),
+        ("Province", &data.reference.provinces, |p| &p.province),
+    ];
+
+    let mut rows: Vec<SpendNormalisedRow> = Vec::new();
+    for (level, table, key) in levels {
+        println!("By {level}");
+        let Some(table) = table else {
+            println!("No {level} reference table loaded\n");
+            continue;
+        };
+        let level_rows = normalised_rows(&data.projects, level, table, key);
+        println!("{}", Table::new(level_rows.clone()));
+        rows.extend(level_rows);
+    }
+
+    if rows.is_empty() {
+        return;
+    }
+
+    export_csv("report16_spend_normalised.csv", &rows, data);
+
+    println!("Full table exported to report16_spend_normalised.csv\n");
+}
@@ -1,5 +1,8 @@
+use crate::services::bootstrap::{intervals, overlaps_neighbour};
+use crate::services::config::{Config, ReliabilityConfig};
+use crate::services::export::{display_option, export_csv};
 use crate::services::loader::DataSet;
-use chrono::NaiveDate;
+use crate::services::project::{Delay, Project};
 use serde::Serialize;
 use std::collections::HashMap;
 use tabled::{Table, Tabled};
@@ -13,67 +16,66 @@ pub struct ContractorReportRow {
     pub contractor: String,
     pub total_cost: f64,
     pub num_projects: usize,
+    pub avg_duration: f64,
     pub avg_delay: f64,
     pub total_savings: f64,
     pub reliability_index: f64,
     pub risk_flag: String,
+    /// Ongoing projects left out of the delay figures by the `exclude` policy.
+    pub incomplete_excluded: usize,
+    /// Days past the target date, whichever delay definition is in use; empty without target dates.
+    #[tabled(display_with = "display_option")]
+    pub avg_schedule_delay: Option<f64>,
+    /// Projects without the dates needed for a delay, or with unknown status.
+    pub delay_missing: usize,
+    #[tabled(display_with = "display_option")]
+    pub total_cost_lower_95: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub total_cost_upper_95: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub avg_delay_lower_95: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub avg_delay_upper_95: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub reliability_index_lower_95: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub reliability_index_upper_95: Option<f64>,
+    /// "Y" when the total cost interval overlaps a neighbouring rank's.
+    pub rank_overlap: String,
 }
 
+type ContractorGroups = HashMap<String, Vec<(f64, f64, Delay, Delay, Delay)>>;
+
 // -----------------------------
 // Utility Functions
 // -----------------------------
-fn parse_f64(v: &str) -> f64 {
-    v.replace(",", "").parse::<f64>().unwrap_or(0.0)
-}
-
-fn parse_date(v: &str) -> Option<NaiveDate> {
-    NaiveDate::parse_from_str(v.trim(), "%Y-%m-%d").ok()
-}
-
 fn round2(value: f64) -> f64 {
     (value * 100.0).round() / 100.0
 }
 
 // -----------------------------
-// Main Report Generator
+// Aggregation
 // -----------------------------
-pub fn generate_report(data: &DataSet) {
-    println!("Generating Top Contractors Performance Report...\n");
-
-    let headers = &data.headers;
-
-    let idx_contractor = headers.iter().position(|h| h == "Contractor").unwrap();
-    let idx_budget = headers
-        .iter()
-        .position(|h| h == "ApprovedBudgetForContract")
-        .unwrap();
-    let idx_cost = headers.iter().position(|h| h == "ContractCost").unwrap();
-    let idx_start = headers.iter().position(|h| h == "StartDate").unwrap();
-    let idx_actual = headers
-        .iter()
-        .position(|h| h == "ActualCompletionDate")
-        .unwrap();
-
-    let mut contractors: HashMap<String, Vec<(f64, f64, i32)>> = HashMap::new();
-
-    for record in &data.matching_records {
-        let contractor = record.get(idx_contractor).unwrap_or("").to_string();
-        let budget = parse_f64(record.get(idx_budget).unwrap_or(""));
-        let cost = parse_f64(record.get(idx_cost).unwrap_or(""));
-        let savings = budget - cost;
-
-        let start = parse_date(record.get(idx_start).unwrap_or(""));
-        let actual = parse_date(record.get(idx_actual).unwrap_or(""));
-
-        let delay_days = match (start, actual) {
-            (Some(s), Some(a)) => (a - s).num_days() as i32,
-            _ => 0,
-        };
-
-        contractors
-            .entry(contractor)
-            .or_default()
-            .push((cost, savings, delay_days));
+/// Every contractor with at least 5 projects, ranked by total cost descending.
+pub fn contractor_rows<'a>(
+    data: &DataSet,
+    projects: impl IntoIterator<Item = &'a Project>,
+    config: &ReliabilityConfig,
+) -> Vec<ContractorReportRow> {
+    let mut contractors: ContractorGroups = HashMap::new();
+
+    // Joint ventures count towards each member firm, with its share of cost and savings
+    for project in projects {
+        // Members credited with nothing (non-leads under `lead`) are not ranked
+        for member in project.members.iter().filter(|m| m.share > 0.0) {
+            contractors.entry(member.name.clone()).or_default().push((
+                project.contract_cost * member.share,
+                project.savings() * member.share,
+                project.delay(&data.delay),
+                project.duration(&data.delay),
+                project.schedule_delay(&data.delay),
+            ));
+        }
     }
 
     let mut rows: Vec<ContractorReportRow> = Vec::new();
@@ -84,19 +86,25 @@ pub fn generate_report(data: &DataSet) {
         }
 
         let num_projects = projects.len();
-        let avg_delay = projects.iter().map(|p| p.2 as f64).sum::<f64>() / num_projects as f64;
+        let avg_delay = Delay::mean_observed(projects.iter().map(|p| &p.2)).unwrap_or(0.0);
+        let avg_duration = Delay::mean_observed(projects.iter().map(|p| &p.3)).unwrap_or(0.0);
+        let avg_schedule_delay = Delay::mean_observed(projects.iter().map(|p| &p.4)).map(round2);
+        let incomplete_excluded = projects.iter().filter(|p| p.2 == Delay::Excluded).count();
+        let delay_missing = projects.iter().filter(|p| p.2 == Delay::Missing).count();
         let total_savings: f64 = projects.iter().map(|p| p.1).sum();
         let total_cost: f64 = projects.iter().map(|p| p.0).sum();
 
         let mut reliability_index = 0.0;
         if total_cost > 0.0 {
-            reliability_index = (1.0 - (avg_delay / 90.0)) * (total_savings / total_cost) * 100.0;
+            reliability_index = (1.0 - (avg_delay / config.delay_denominator_days))
+                * (total_savings / total_cost)
+                * 100.0;
         }
         if reliability_index > 100.0 {
             reliability_index = 100.0;
         }
 
-        let risk_flag = if reliability_index < 50.0 {
+        let risk_flag = if reliability_index < config.high_risk_cutoff {
             "High Risk".to_string()
         } else {
             "".to_string()
@@ -106,11 +114,22 @@ pub fn generate_report(data: &DataSet) {
             rank: 0, // temporary, will assign after sorting
             contractor,
             total_cost: round2(total_cost),
+            total_cost_lower_95: None,
+            total_cost_upper_95: None,
             num_projects,
+            avg_duration: round2(avg_duration),
             avg_delay: round2(avg_delay),
+            avg_delay_lower_95: None,
+            avg_delay_upper_95: None,
             total_savings: round2(total_savings),
             reliability_index: round2(reliability_index),
+            reliability_index_lower_95: None,
+            reliability_index_upper_95: None,
             risk_flag,
+            rank_overlap: String::new(),
+            incomplete_excluded,
+            avg_schedule_delay,
+            delay_missing,
         });
     }
 
@@ -122,6 +141,76 @@ pub fn generate_report(data: &DataSet) {
         row.rank = i + 1;
     }
 
+    rows
+}
+
+/// Fills the 95% interval columns by resampling projects within each contractor.
+fn add_intervals(rows: &mut [ContractorReportRow], data: &DataSet, config: &Config) {
+    let bounds = intervals(
+        data,
+        &config.bootstrap,
+        // Same member firms as `contractor_rows`, so each firm keeps its project count
+        |p| {
+            p.members
+                .iter()
+                .map(|m| m.name.as_str())
+                .collect::<Vec<_>>()
+                .join(" / ")
+        },
+        |sample| {
+            contractor_rows(data, sample.iter().copied(), &config.reliability)
+                .into_iter()
+                .map(|r| {
+                    let values = vec![r.total_cost, r.avg_delay, r.reliability_index];
+                    (r.contractor, values)
+                })
+                .collect()
+        },
+    );
+
+    for row in rows.iter_mut() {
+        if let Some(b) = bounds.get(&row.contractor) {
+            row.total_cost_lower_95 = Some(round2(b[0].0));
+            row.total_cost_upper_95 = Some(round2(b[0].1));
+            row.avg_delay_lower_95 = Some(round2(b[1].0));
+            row.avg_delay_upper_95 = Some(round2(b[1].1));
+            row.reliability_index_lower_95 = Some(round2(b[2].0));
+            row.reliability_index_upper_95 = Some(round2(b[2].1));
+        }
+    }
+
+    let cost_bounds: Vec<(f64, f64)> = rows
+        .iter()
+        .map(|r| {
+            (
+                r.total_cost_lower_95.unwrap_or(r.total_cost),
+                r.total_cost_upper_95.unwrap_or(r.total_cost),
+            )
+        })
+        .collect();
+    for (row, overlap) in rows.iter_mut().zip(overlaps_neighbour(&cost_bounds)) {
+        row.rank_overlap = if overlap { "Y" } else { "" }.to_string();
+    }
+}
+
+/// Every ranked contractor, with intervals when bootstrap is enabled; the report prints the top 15.
+pub fn report_rows(data: &DataSet, config: &Config) -> Vec<ContractorReportRow> {
+    let mut rows = contractor_rows(data, &data.projects, &config.reliability);
+
+    if config.bootstrap.enabled {
+        add_intervals(&mut rows, data, config);
+    }
+    rows
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+pub fn generate_report(data: &DataSet, config: &Config) {
+    println!("Generating Top Contractors Performance Report...\n");
+
+    let mut rows = report_rows(data, config);
+
     // Keep top 15
     rows.truncate(15);
 
@@ -130,11 +219,7 @@ pub fn generate_report(data: &DataSet) {
     println!("{table}");
 
     // Export CSV
-    let mut wtr = csv::Writer::from_path("report2_contractor_ranking.csv").unwrap();
-    for row in rows {
-        wtr.serialize(row).unwrap();
-    }
-    wtr.flush().unwrap();
+    export_csv("report2_contractor_ranking.csv", &rows, data);
 
     println!("Full table exported to report2_contractor_ranking.csv\n");
 }
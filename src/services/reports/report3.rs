@@ -1,6 +1,10 @@
+use crate::services::bootstrap::{intervals, overlaps_neighbour};
+use crate::services::config::{Config, TrendConfig};
+use crate::services::export::{display_option, export_csv};
 use crate::services::loader::DataSet;
+use crate::services::project::Project;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use tabled::{Table, Tabled};
 
 // -----------------------------
@@ -13,97 +17,136 @@ pub struct ProjectTypeReportRow {
     pub total_projects: usize,
     pub avg_savings: f64,
     pub overrun_rate: f64,
-    pub yoy_change: f64,
+    /// Percentage change in average savings from the previous year.
+    #[tabled(display_with = "display_option")]
+    pub yoy_change: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub yoy_change_amount: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub base_year_change: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub base_year_change_pct: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub cagr_pct: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub avg_savings_lower_95: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub avg_savings_upper_95: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub overrun_rate_lower_95: Option<f64>,
+    #[tabled(display_with = "display_option")]
+    pub overrun_rate_upper_95: Option<f64>,
+    /// "Y" when the average savings interval overlaps a neighbouring rank's in the same year.
+    pub rank_overlap: String,
 }
 
 // -----------------------------
 // Utility Functions
 // -----------------------------
-fn parse_f64(v: &str) -> f64 {
-    v.replace(",", "").parse::<f64>().unwrap_or(0.0)
-}
-
 fn round2(value: f64) -> f64 {
     (value * 100.0).round() / 100.0
 }
 
-// -----------------------------
-// Main Report Generator
-// -----------------------------
-pub fn generate_report(data: &DataSet) {
-    println!("Generating Annual Project Type Cost Overrun Trends...\n");
-
-    let headers = &data.headers;
+/// Percentage change relative to the size of the baseline, so a move from -100 to -50
+/// reads as +50% (an improvement) rather than -50%. Undefined for a zero baseline.
+fn pct_change(current: f64, baseline: f64) -> Option<f64> {
+    if baseline.abs() < f64::EPSILON {
+        None
+    } else {
+        Some((current - baseline) / baseline.abs() * 100.0)
+    }
+}
 
-    let idx_year = headers.iter().position(|h| h == "FundingYear").unwrap();
-    let idx_type = headers.iter().position(|h| h == "TypeOfWork").unwrap();
-    let idx_budget = headers
-        .iter()
-        .position(|h| h == "ApprovedBudgetForContract")
-        .unwrap();
-    let idx_cost = headers.iter().position(|h| h == "ContractCost").unwrap();
+/// Compound annual growth rate between two years; only defined for positive values.
+fn cagr(first: (i32, f64), last: (i32, f64)) -> Option<f64> {
+    let years = last.0 - first.0;
+    if years <= 0 || first.1 <= 0.0 || last.1 <= 0.0 {
+        return None;
+    }
+    Some(((last.1 / first.1).powf(1.0 / years as f64) - 1.0) * 100.0)
+}
 
+// -----------------------------
+// Aggregation
+// -----------------------------
+/// Per (FundingYear, TypeOfWork) rows, sorted by year then average savings. YoY compares with
+/// the previous calendar year and base-year change with `config.base_year` (the earliest
+/// year in the data when unset); both are blank when that year has no projects of the type.
+pub fn project_type_rows<'a>(
+    projects: impl IntoIterator<Item = &'a Project>,
+    config: &TrendConfig,
+) -> Vec<ProjectTypeReportRow> {
     // Group by (FundingYear, TypeOfWork)
     let mut groups: HashMap<(String, String), Vec<f64>> = HashMap::new();
 
-    for record in &data.matching_records {
-        let year = record.get(idx_year).unwrap_or("").to_string();
-        let work_type = record.get(idx_type).unwrap_or("").to_string();
-        let budget = parse_f64(record.get(idx_budget).unwrap_or(""));
-        let cost = parse_f64(record.get(idx_cost).unwrap_or(""));
-        let savings = budget - cost; // negative if overrun
+    for project in projects {
+        let savings = project.savings(); // negative if overrun
 
-        groups.entry((year, work_type)).or_default().push(savings);
+        groups
+            .entry((project.funding_year.clone(), project.type_of_work.clone()))
+            .or_default()
+            .push(savings);
     }
 
-    // Collect unique years in ascending order
-    let mut years: Vec<String> = groups.keys().map(|(y, _)| y.clone()).collect();
-    years.sort();
-    years.dedup();
+    // Average savings per TypeOfWork and year, for the change columns
+    let mut by_type: HashMap<&str, BTreeMap<i32, f64>> = HashMap::new();
+    for ((year, work_type), savings_list) in &groups {
+        if let Ok(year) = year.trim().parse::<i32>() {
+            let avg = savings_list.iter().sum::<f64>() / savings_list.len() as f64;
+            by_type.entry(work_type).or_default().insert(year, avg);
+        }
+    }
+
+    let base_year = config.base_year.or_else(|| {
+        by_type
+            .values()
+            .filter_map(|years| years.keys().next())
+            .min()
+            .copied()
+    });
 
-    // Baselines for YoY (2021)
-    let mut baselines: HashMap<String, f64> = HashMap::new();
     let mut rows: Vec<ProjectTypeReportRow> = Vec::new();
 
-    for year in &years {
-        let year_groups: Vec<_> = groups.iter().filter(|((y, _), _)| y == year).collect();
-
-        for ((_, work_type), savings_list) in year_groups {
-            let total_projects = savings_list.len();
-            let avg_savings = if total_projects == 0 {
-                0.0
-            } else {
-                savings_list.iter().sum::<f64>() / total_projects as f64
-            };
-            let overrun_count = savings_list.iter().filter(|s| **s < 0.0).count();
-            let overrun_rate = if total_projects == 0 {
-                0.0
-            } else {
-                overrun_count as f64 / total_projects as f64 * 100.0
-            };
-
-            // YoY change
-            let yoy_change = if year == "2021" {
-                baselines.insert(work_type.clone(), avg_savings);
-                0.0
-            } else {
-                let baseline = baselines.get(work_type).cloned().unwrap_or(avg_savings);
-                if baseline.abs() < f64::EPSILON {
-                    0.0
-                } else {
-                    (avg_savings - baseline) / baseline * 100.0
-                }
-            };
-
-            rows.push(ProjectTypeReportRow {
-                funding_year: year.clone(),
-                type_of_work: work_type.clone(),
-                total_projects,
-                avg_savings: round2(avg_savings),
-                overrun_rate: round2(overrun_rate),
-                yoy_change: round2(yoy_change),
-            });
-        }
+    for ((year, work_type), savings_list) in &groups {
+        let total_projects = savings_list.len();
+        let avg_savings = savings_list.iter().sum::<f64>() / total_projects as f64;
+        let overrun_count = savings_list.iter().filter(|s| **s < 0.0).count();
+        let overrun_rate = overrun_count as f64 / total_projects as f64 * 100.0;
+
+        let history = by_type.get(work_type.as_str());
+        let year_num = year.trim().parse::<i32>().ok();
+
+        let previous = year_num
+            .zip(history)
+            .and_then(|(y, h)| h.get(&(y - 1)).copied());
+        let base = base_year
+            .filter(|b| Some(*b) != year_num)
+            .zip(history)
+            .and_then(|(b, h)| h.get(&b).copied());
+        let cagr_pct = history.and_then(|h| {
+            let (first, last) = (h.iter().next()?, h.iter().next_back()?);
+            cagr((*first.0, *first.1), (*last.0, *last.1))
+        });
+
+        rows.push(ProjectTypeReportRow {
+            funding_year: year.clone(),
+            type_of_work: work_type.clone(),
+            total_projects,
+            avg_savings: round2(avg_savings),
+            avg_savings_lower_95: None,
+            avg_savings_upper_95: None,
+            overrun_rate: round2(overrun_rate),
+            overrun_rate_lower_95: None,
+            overrun_rate_upper_95: None,
+            rank_overlap: String::new(),
+            yoy_change: previous
+                .and_then(|p| pct_change(avg_savings, p))
+                .map(round2),
+            yoy_change_amount: previous.map(|p| round2(avg_savings - p)),
+            base_year_change: base.map(|b| round2(avg_savings - b)),
+            base_year_change_pct: base.and_then(|b| pct_change(avg_savings, b)).map(round2),
+            cagr_pct: cagr_pct.map(round2),
+        });
     }
 
     // Sort by FundingYear ascending, AvgSavings descending
@@ -113,16 +156,80 @@ pub fn generate_report(data: &DataSet) {
             .then(b.avg_savings.partial_cmp(&a.avg_savings).unwrap())
     });
 
+    rows
+}
+
+/// Fills the 95% interval columns by resampling projects within each year and TypeOfWork.
+fn add_intervals(rows: &mut [ProjectTypeReportRow], data: &DataSet, config: &Config) {
+    let key = |year: &str, work_type: &str| format!("{year}|{work_type}");
+    let bounds = intervals(
+        data,
+        &config.bootstrap,
+        |p| key(&p.funding_year, &p.type_of_work),
+        |sample| {
+            project_type_rows(sample.iter().copied(), &config.trends)
+                .into_iter()
+                .map(|r| {
+                    let values = vec![r.avg_savings, r.overrun_rate];
+                    (key(&r.funding_year, &r.type_of_work), values)
+                })
+                .collect()
+        },
+    );
+
+    for row in rows.iter_mut() {
+        if let Some(b) = bounds.get(&key(&row.funding_year, &row.type_of_work)) {
+            row.avg_savings_lower_95 = Some(round2(b[0].0));
+            row.avg_savings_upper_95 = Some(round2(b[0].1));
+            row.overrun_rate_lower_95 = Some(round2(b[1].0));
+            row.overrun_rate_upper_95 = Some(round2(b[1].1));
+        }
+    }
+
+    // Rows are ranked by average savings within each year
+    for year_rows in rows.chunk_by_mut(|a, b| a.funding_year == b.funding_year) {
+        let savings_bounds: Vec<(f64, f64)> = year_rows
+            .iter()
+            .map(|r| {
+                (
+                    r.avg_savings_lower_95.unwrap_or(r.avg_savings),
+                    r.avg_savings_upper_95.unwrap_or(r.avg_savings),
+                )
+            })
+            .collect();
+        for (row, overlap) in year_rows
+            .iter_mut()
+            .zip(overlaps_neighbour(&savings_bounds))
+        {
+            row.rank_overlap = if overlap { "Y" } else { "" }.to_string();
+        }
+    }
+}
+
+/// One row per year and type of work, with intervals when bootstrap is enabled.
+pub fn report_rows(data: &DataSet, config: &Config) -> Vec<ProjectTypeReportRow> {
+    let mut rows = project_type_rows(&data.projects, &config.trends);
+
+    if config.bootstrap.enabled {
+        add_intervals(&mut rows, data, config);
+    }
+    rows
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+pub fn generate_report(data: &DataSet, config: &Config) {
+    println!("Generating Annual Project Type Cost Overrun Trends...\n");
+
+    let rows = report_rows(data, config);
+
     // Assign table
     let table = Table::new(rows.clone());
     println!("{table}");
 
     // Export CSV
-    let mut wtr = csv::Writer::from_path("report3_project_type_trends.csv").unwrap();
-    for row in rows {
-        wtr.serialize(row).unwrap();
-    }
-    wtr.flush().unwrap();
+    export_csv("report3_project_type_trends.csv", &rows, data);
 
     println!("Full table exported to report3_project_type_trends.csv\n");
 }
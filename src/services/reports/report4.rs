@@ -0,0 +1,137 @@
+use crate::services::export::export_csv;
+use crate::services::loader::DataSet;
+use crate::services::project::Project;
+use serde::Serialize;
+use std::collections::HashMap;
+use tabled::{Table, Tabled};
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct ConcentrationReportRow {
+    pub scope: String,
+    pub group: String,
+    pub num_contractors: usize,
+    pub total_cost: f64,
+    pub hhi: f64,
+    pub top1_share: f64,
+    pub top5_share: f64,
+    pub top10_share: f64,
+    pub concentration: String,
+}
+
+// HHI thresholds on the 0-10,000 scale (US DOJ merger guidelines)
+const HHI_MODERATE: f64 = 1500.0;
+const HHI_HIGH: f64 = 2500.0;
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn classify(hhi: f64) -> String {
+    if hhi >= HHI_HIGH {
+        "Highly Concentrated".to_string()
+    } else if hhi >= HHI_MODERATE {
+        "Moderately Concentrated".to_string()
+    } else {
+        "".to_string()
+    }
+}
+
+/// Builds one row from the contract cost won by each contractor in a market.
+fn concentration_row(
+    scope: &str,
+    group: String,
+    shares: HashMap<&str, f64>,
+) -> ConcentrationReportRow {
+    let mut costs: Vec<f64> = shares.into_values().collect();
+    costs.sort_by(|a, b| b.partial_cmp(a).unwrap());
+
+    let total_cost: f64 = costs.iter().sum();
+    let share_pct = |c: f64| {
+        if total_cost > 0.0 {
+            c / total_cost * 100.0
+        } else {
+            0.0
+        }
+    };
+
+    let hhi: f64 = costs.iter().map(|c| share_pct(*c).powi(2)).sum();
+    let top_n = |n: usize| share_pct(costs.iter().take(n).sum());
+
+    ConcentrationReportRow {
+        scope: scope.to_string(),
+        group,
+        num_contractors: costs.len(),
+        total_cost: round2(total_cost),
+        hhi: round2(hhi),
+        top1_share: round2(top_n(1)),
+        top5_share: round2(top_n(5)),
+        top10_share: round2(top_n(10)),
+        concentration: classify(hhi),
+    }
+}
+
+/// Computes one row per distinct key, sorted by HHI descending.
+fn rows_by<F>(projects: &[Project], scope: &str, key: F) -> Vec<ConcentrationReportRow>
+where
+    F: Fn(&Project) -> &str,
+{
+    let mut markets: HashMap<&str, HashMap<&str, f64>> = HashMap::new();
+
+    // Joint ventures count towards each member firm, with its share of the cost
+    for project in projects {
+        let market = markets.entry(key(project)).or_default();
+        for member in &project.members {
+            *market.entry(member.name.as_str()).or_default() +=
+                project.contract_cost * member.share;
+        }
+    }
+
+    let mut rows: Vec<ConcentrationReportRow> = markets
+        .into_iter()
+        .map(|(group, shares)| concentration_row(scope, group.to_string(), shares))
+        .collect();
+
+    rows.sort_by(|a, b| b.hhi.partial_cmp(&a.hhi).unwrap());
+    rows
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+pub fn generate_report(data: &DataSet) {
+    println!("Generating Contractor Market Concentration Report...\n");
+
+    let projects = &data.projects;
+
+    let mut rows: Vec<ConcentrationReportRow> = Vec::new();
+    rows.extend(rows_by(projects, "National", |_| "Philippines"));
+    rows.extend(rows_by(projects, "Region", |p| &p.region));
+    rows.extend(rows_by(projects, "Province", |p| &p.province));
+
+    // Funding years read best in chronological order
+    let mut by_year = rows_by(projects, "FundingYear", |p| &p.funding_year);
+    by_year.sort_by(|a, b| a.group.cmp(&b.group));
+    rows.extend(by_year);
+
+    let table = Table::new(rows.clone());
+    println!("{table}");
+
+    let flagged: Vec<&str> = rows
+        .iter()
+        .filter(|r| r.scope == "Region" && r.hhi >= HHI_HIGH)
+        .map(|r| r.group.as_str())
+        .collect();
+    if !flagged.is_empty() {
+        println!("Highly concentrated regions: {}\n", flagged.join(", "));
+    }
+
+    export_csv("report4_market_concentration.csv", &rows, data);
+
+    println!("Full table exported to report4_market_concentration.csv\n");
+}
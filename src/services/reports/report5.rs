@@ -0,0 +1,171 @@
+use crate::services::config::RedFlagsConfig;
+use crate::services::export::export_csv;
+use crate::services::loader::DataSet;
+use crate::services::project::Project;
+use serde::Serialize;
+use std::collections::HashMap;
+use tabled::{Table, Tabled};
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct RedFlagReportRow {
+    pub flag_id: String,
+    pub flag_type: String,
+    pub contractor: String,
+    pub location: String,
+    pub type_of_work: String,
+    pub project_id: String,
+    pub start_date: String,
+    pub approved_budget: f64,
+    pub contract_cost: f64,
+    pub detail: String,
+}
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn flag_row(flag_id: &str, flag_type: &str, project: &Project, detail: String) -> RedFlagReportRow {
+    RedFlagReportRow {
+        flag_id: flag_id.to_string(),
+        flag_type: flag_type.to_string(),
+        contractor: project.contractor.clone(),
+        location: project.location(),
+        type_of_work: project.type_of_work.clone(),
+        project_id: project.project_id.clone(),
+        start_date: project
+            .start_date
+            .map(|d| d.to_string())
+            .unwrap_or_default(),
+        approved_budget: round2(project.approved_budget),
+        contract_cost: round2(project.contract_cost),
+        detail,
+    }
+}
+
+/// Same contractor, place and type of work, starting within the window of the cluster's
+/// first start date.
+fn split_contracts(projects: &[Project], window_days: i64) -> Vec<RedFlagReportRow> {
+    let mut groups: HashMap<(&str, String, &str), Vec<&Project>> = HashMap::new();
+
+    for project in projects.iter().filter(|p| p.start_date.is_some()) {
+        groups
+            .entry((
+                &project.contractor,
+                project.location(),
+                &project.type_of_work,
+            ))
+            .or_default()
+            .push(project);
+    }
+
+    let mut clusters: Vec<Vec<&Project>> = Vec::new();
+    for (_, mut group) in groups {
+        group.sort_by_key(|p| p.start_date);
+
+        let mut current: Vec<&Project> = Vec::new();
+        for project in group {
+            if let Some(first) = current.first()
+                && (project.start_date.unwrap() - first.start_date.unwrap()).num_days()
+                    > window_days
+            {
+                clusters.push(std::mem::take(&mut current));
+            }
+            current.push(project);
+        }
+        clusters.push(current);
+    }
+
+    clusters.retain(|c| c.len() > 1);
+    clusters.sort_by(|a, b| {
+        let total = |c: &Vec<&Project>| c.iter().map(|p| p.contract_cost).sum::<f64>();
+        total(b).partial_cmp(&total(a)).unwrap()
+    });
+
+    let mut rows = Vec::new();
+    for (i, cluster) in clusters.iter().enumerate() {
+        let flag_id = format!("SPLIT-{:03}", i + 1);
+        let total_cost: f64 = cluster.iter().map(|p| p.contract_cost).sum();
+        let detail = format!(
+            "{} projects within {} days, combined cost {:.2}",
+            cluster.len(),
+            window_days,
+            total_cost
+        );
+
+        for project in cluster {
+            rows.push(flag_row(
+                &flag_id,
+                "Contract Splitting",
+                project,
+                detail.clone(),
+            ));
+        }
+    }
+    rows
+}
+
+/// Contract cost just below the approved budget for the contract, within the configured band.
+fn near_ceiling_bids(projects: &[Project], config: &RedFlagsConfig) -> Vec<RedFlagReportRow> {
+    let mut flagged: Vec<(&Project, f64)> = projects
+        .iter()
+        .filter(|p| p.approved_budget > 0.0)
+        .map(|p| (p, p.savings() / p.approved_budget * 100.0))
+        .filter(|(_, gap_pct)| {
+            (config.near_ceiling_min_pct..=config.near_ceiling_pct).contains(gap_pct)
+        })
+        .collect();
+
+    flagged.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+    flagged
+        .into_iter()
+        .enumerate()
+        .map(|(i, (project, gap_pct))| {
+            let detail = format!("cost is {gap_pct:.3}% below approved budget");
+            flag_row(
+                &format!("BID-{:03}", i + 1),
+                "Near-Ceiling Bid",
+                project,
+                detail,
+            )
+        })
+        .collect()
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+pub fn generate_report(data: &DataSet, config: &RedFlagsConfig) {
+    println!("Generating Suspicious Contract Pattern Report...\n");
+
+    let splits = split_contracts(&data.projects, config.split_window_days);
+    let bids = near_ceiling_bids(&data.projects, config);
+
+    println!(
+        "Contract splitting: {} projects flagged (start dates within {} days)",
+        splits.len(),
+        config.split_window_days
+    );
+    println!(
+        "Near-ceiling bids: {} projects flagged ({}-{}% below approved budget)\n",
+        bids.len(),
+        config.near_ceiling_min_pct,
+        config.near_ceiling_pct
+    );
+
+    let mut rows = splits;
+    rows.extend(bids);
+
+    let table = Table::new(rows.clone());
+    println!("{table}");
+
+    export_csv("report5_red_flags.csv", &rows, data);
+
+    println!("Full table exported to report5_red_flags.csv\n");
+}
@@ -0,0 +1,340 @@
+use crate::services::config::BenfordConfig;
+use crate::services::export::export_csv;
+use crate::services::loader::DataSet;
+use crate::services::project::Project;
+use serde::Serialize;
+use std::collections::HashMap;
+use tabled::{Table, Tabled};
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct BenfordDigitRow {
+    pub field: String,
+    pub test: String,
+    pub digits: u32,
+    pub count: usize,
+    pub observed_pct: f64,
+    pub expected_pct: f64,
+    pub difference: f64,
+}
+
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct BenfordConformityRow {
+    pub field: String,
+    pub test: String,
+    pub scope: String,
+    pub group: String,
+    pub num_values: usize,
+    pub chi_square: f64,
+    pub chi_square_critical: f64,
+    pub mad: f64,
+    pub conformity: String,
+}
+
+type AmountField = (&'static str, fn(&Project) -> f64);
+
+#[derive(Clone, Copy)]
+enum DigitTest {
+    First,
+    FirstTwo,
+}
+
+impl DigitTest {
+    fn name(self) -> &'static str {
+        match self {
+            DigitTest::First => "First Digit",
+            DigitTest::FirstTwo => "First Two Digits",
+        }
+    }
+
+    fn range(self) -> std::ops::RangeInclusive<u32> {
+        match self {
+            DigitTest::First => 1..=9,
+            DigitTest::FirstTwo => 10..=99,
+        }
+    }
+
+    // Chi-square critical value at 5% significance (8 and 89 degrees of freedom)
+    fn chi_square_critical(self) -> f64 {
+        match self {
+            DigitTest::First => 15.507,
+            DigitTest::FirstTwo => 112.022,
+        }
+    }
+
+    // Nigrini's MAD conformity cut-offs: close, acceptable, marginal
+    fn mad_thresholds(self) -> [f64; 3] {
+        match self {
+            DigitTest::First => [0.006, 0.012, 0.015],
+            DigitTest::FirstTwo => [0.0012, 0.0018, 0.0022],
+        }
+    }
+}
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn round4(value: f64) -> f64 {
+    (value * 10000.0).round() / 10000.0
+}
+
+/// Leading digits of a positive amount, e.g. 12 for 1,234.56 under the two-digit test.
+fn leading_digits(value: f64, test: DigitTest) -> Option<u32> {
+    let take = match test {
+        DigitTest::First => 1,
+        DigitTest::FirstTwo => 2,
+    };
+    if value.is_nan() || value < 10f64.powi(take as i32 - 1) {
+        return None;
+    }
+
+    let mantissa: String = format!("{value:e}")
+        .chars()
+        .take_while(|c| *c != 'e')
+        .filter(|c| c.is_ascii_digit())
+        .collect();
+
+    format!("{mantissa:0<2}")[..take].parse().ok()
+}
+
+fn expected_share(digits: u32) -> f64 {
+    (1.0 + 1.0 / digits as f64).log10()
+}
+
+fn counts(values: &[f64], test: DigitTest) -> (HashMap<u32, usize>, usize) {
+    let mut counts: HashMap<u32, usize> = HashMap::new();
+    let mut total = 0;
+    for d in values.iter().filter_map(|v| leading_digits(*v, test)) {
+        *counts.entry(d).or_default() += 1;
+        total += 1;
+    }
+    (counts, total)
+}
+
+fn digit_rows(field: &str, values: &[f64], test: DigitTest) -> Vec<BenfordDigitRow> {
+    let (counts, total) = counts(values, test);
+
+    test.range()
+        .map(|d| {
+            let count = counts.get(&d).copied().unwrap_or(0);
+            let observed = if total > 0 {
+                count as f64 / total as f64
+            } else {
+                0.0
+            };
+            let expected = expected_share(d);
+            BenfordDigitRow {
+                field: field.to_string(),
+                test: test.name().to_string(),
+                digits: d,
+                count,
+                observed_pct: round2(observed * 100.0),
+                expected_pct: round2(expected * 100.0),
+                difference: round2((observed - expected) * 100.0),
+            }
+        })
+        .collect()
+}
+
+fn conformity_row(
+    field: &str,
+    scope: &str,
+    group: String,
+    values: &[f64],
+    test: DigitTest,
+) -> BenfordConformityRow {
+    let (counts, total) = counts(values, test);
+
+    let mut chi_square = 0.0;
+    let mut abs_dev = 0.0;
+    for d in test.range() {
+        let observed = counts.get(&d).copied().unwrap_or(0) as f64;
+        let expected = expected_share(d) * total as f64;
+        if expected > 0.0 {
+            chi_square += (observed - expected).powi(2) / expected;
+        }
+        if total > 0 {
+            abs_dev += (observed / total as f64 - expected_share(d)).abs();
+        }
+    }
+    let mad = abs_dev / test.range().count() as f64;
+
+    let [close, acceptable, marginal] = test.mad_thresholds();
+    let conformity = if mad <= close {
+        "Close Conformity"
+    } else if mad <= acceptable {
+        "Acceptable Conformity"
+    } else if mad <= marginal {
+        "Marginal Conformity"
+    } else {
+        "Nonconformity"
+    };
+
+    BenfordConformityRow {
+        field: field.to_string(),
+        test: test.name().to_string(),
+        scope: scope.to_string(),
+        group,
+        num_values: total,
+        chi_square: round2(chi_square),
+        chi_square_critical: test.chi_square_critical(),
+        mad: round4(mad),
+        conformity: conformity.to_string(),
+    }
+}
+
+/// First-digit conformity per group with at least `min_projects` projects, worst first.
+fn breakdown<K, V>(
+    projects: &[Project],
+    field: &str,
+    scope: &str,
+    min_projects: usize,
+    key: K,
+    value: V,
+) -> Vec<BenfordConformityRow>
+where
+    K: Fn(&Project) -> &str,
+    V: Fn(&Project) -> f64,
+{
+    let mut groups: HashMap<&str, Vec<f64>> = HashMap::new();
+    for project in projects {
+        groups.entry(key(project)).or_default().push(value(project));
+    }
+
+    let mut rows: Vec<BenfordConformityRow> = groups
+        .into_iter()
+        .filter(|(_, values)| values.len() >= min_projects)
+        .map(|(group, values)| {
+            conformity_row(field, scope, group.to_string(), &values, DigitTest::First)
+        })
+        .collect();
+
+    rows.sort_by(|a, b| b.mad.partial_cmp(&a.mad).unwrap());
+    rows
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+pub fn generate_report(data: &DataSet, config: &BenfordConfig) {
+    println!("Generating Benford's Law Digit Analysis...\n");
+
+    let fields: [AmountField; 2] = [
+        ("ContractCost", |p| p.contract_cost),
+        ("ApprovedBudgetForContract", |p| p.approved_budget),
+    ];
+
+    let mut digit_table: Vec<BenfordDigitRow> = Vec::new();
+    let mut conformity: Vec<BenfordConformityRow> = Vec::new();
+
+    for (field, value) in fields {
+        let values: Vec<f64> = data.projects.iter().map(value).collect();
+
+        for test in [DigitTest::First, DigitTest::FirstTwo] {
+            digit_table.extend(digit_rows(field, &values, test));
+            conformity.push(conformity_row(
+                field,
+                "National",
+                "Philippines".to_string(),
+                &values,
+                test,
+            ));
+        }
+
+        conformity.extend(breakdown(
+            &data.projects,
+            field,
+            "Region",
+            config.min_projects,
+            |p| &p.region,
+            value,
+        ));
+        conformity.extend(breakdown(
+            &data.projects,
+            field,
+            "Contractor",
+            config.min_projects,
+            |p| &p.contractor,
+            value,
+        ));
+    }
+
+    // The two-digit table is long, so only the first-digit rows are printed
+    let first_digit: Vec<BenfordDigitRow> = digit_table
+        .iter()
+        .filter(|r| r.digits < 10)
+        .cloned()
+        .collect();
+    println!("{}", Table::new(first_digit));
+    println!("{}", Table::new(conformity.clone()));
+
+    export_csv("report6_benford_digits.csv", &digit_table, data);
+
+    export_csv("report6_benford_conformity.csv", &conformity, data);
+
+    println!(
+        "Full tables exported to report6_benford_digits.csv and report6_benford_conformity.csv\n"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leading_digits_of_amounts() {
+        assert_eq!(leading_digits(1234.56, DigitTest::First), Some(1));
+        assert_eq!(leading_digits(1234.56, DigitTest::FirstTwo), Some(12));
+        assert_eq!(leading_digits(50.0, DigitTest::FirstTwo), Some(50));
+        // Amounts too small to have the digits tested are skipped
+        assert_eq!(leading_digits(5.0, DigitTest::FirstTwo), None);
+        assert_eq!(leading_digits(0.0987, DigitTest::First), None);
+        assert_eq!(leading_digits(0.0, DigitTest::First), None);
+        assert_eq!(leading_digits(-300.0, DigitTest::First), None);
+    }
+
+    #[test]
+    fn benford_amounts_conform_and_uniform_ones_do_not() {
+        // Leading digits in Benford's proportions, to the nearest of 10,000 values
+        let benford: Vec<f64> = (1..=9)
+            .flat_map(|d| {
+                let n = (expected_share(d) * 10_000.0).round() as usize;
+                std::iter::repeat_n(d as f64 * 100.0, n)
+            })
+            .collect();
+        let row = conformity_row(
+            "ContractCost",
+            "National",
+            String::new(),
+            &benford,
+            DigitTest::First,
+        );
+        assert_eq!(row.conformity, "Close Conformity");
+        assert!(
+            row.chi_square < row.chi_square_critical,
+            "{}",
+            row.chi_square
+        );
+
+        let uniform: Vec<f64> = (1..=9).flat_map(|d| [d as f64 * 100.0; 100]).collect();
+        let row = conformity_row(
+            "ContractCost",
+            "National",
+            String::new(),
+            &uniform,
+            DigitTest::First,
+        );
+        assert_eq!(row.conformity, "Nonconformity");
+        assert!(
+            row.chi_square > row.chi_square_critical,
+            "{}",
+            row.chi_square
+        );
+    }
+}
@@ -0,0 +1,337 @@
+use crate::services::config::{Config, OutlierConfig};
+use crate::services::export::{export_csv, export_json};
+use crate::services::loader::DataSet;
+use crate::services::project::{DelayDefinition, DelayOptions, Project};
+use crate::services::reports::{report1, report2, report3, summary};
+use crate::services::stats::{mad, median, quantile, sorted};
+use chrono::Duration;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use tabled::{Table, Tabled};
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct OutlierReportRow {
+    pub row_number: usize,
+    pub project_id: String,
+    pub region: String,
+    pub type_of_work: String,
+    pub contractor: String,
+    pub metric: String,
+    pub value: f64,
+    pub group_median: f64,
+    pub robust_z: f64,
+    pub lower_fence: f64,
+    pub upper_fence: f64,
+    pub method: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Metric {
+    Savings,
+    SavingsPct,
+    DelayDays,
+}
+
+impl Metric {
+    const ALL: [Metric; 3] = [Metric::Savings, Metric::SavingsPct, Metric::DelayDays];
+
+    fn name(self) -> &'static str {
+        match self {
+            Metric::Savings => "Savings",
+            Metric::SavingsPct => "SavingsPctOfABC",
+            Metric::DelayDays => "DelayDays",
+        }
+    }
+
+    fn value(self, project: &Project, options: &DelayOptions) -> Option<f64> {
+        match self {
+            Metric::Savings => Some(project.savings()),
+            Metric::SavingsPct if project.approved_budget > 0.0 => {
+                Some(project.savings() / project.approved_budget * 100.0)
+            }
+            Metric::SavingsPct => None,
+            Metric::DelayDays => project.delay(options).observed().map(|d| d as f64),
+        }
+    }
+}
+
+/// IQR fences per (TypeOfWork, Region, Metric), kept for winsorising.
+type Fences = HashMap<(String, String, Metric), (f64, f64)>;
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn find_outliers(
+    projects: &[Project],
+    options: &DelayOptions,
+    config: &OutlierConfig,
+) -> (Vec<OutlierReportRow>, Fences) {
+    let mut groups: HashMap<(&str, &str), Vec<&Project>> = HashMap::new();
+    for project in projects {
+        groups
+            .entry((&project.type_of_work, &project.region))
+            .or_default()
+            .push(project);
+    }
+
+    let mut rows = Vec::new();
+    let mut fences = Fences::new();
+
+    for ((type_of_work, region), members) in groups {
+        for metric in Metric::ALL {
+            let values: Vec<(&Project, f64)> = members
+                .iter()
+                .filter_map(|p| metric.value(p, options).map(|v| (*p, v)))
+                .collect();
+            if values.len() < config.min_group_size {
+                continue;
+            }
+
+            let raw: Vec<f64> = values.iter().map(|(_, v)| *v).collect();
+            let ordered = sorted(&raw);
+            let center = median(&raw);
+            let spread = mad(&raw);
+            let (q1, q3) = (quantile(&ordered, 0.25), quantile(&ordered, 0.75));
+            let iqr = q3 - q1;
+            let lower = q1 - config.iqr_multiplier * iqr;
+            let upper = q3 + config.iqr_multiplier * iqr;
+
+            fences.insert(
+                (type_of_work.to_string(), region.to_string(), metric),
+                (lower, upper),
+            );
+
+            for (project, value) in values {
+                // 0.6745 scales the MAD to the standard deviation of a normal distribution
+                let robust_z = if spread > 0.0 {
+                    0.6745 * (value - center) / spread
+                } else {
+                    0.0
+                };
+                let z_flag = robust_z.abs() > config.z_threshold;
+                let iqr_flag = value < lower || value > upper;
+
+                let method = match (z_flag, iqr_flag) {
+                    (true, true) => "Robust Z + IQR",
+                    (true, false) => "Robust Z",
+                    (false, true) => "IQR",
+                    (false, false) => continue,
+                };
+
+                rows.push(OutlierReportRow {
+                    row_number: project.row_number,
+                    project_id: project.project_id.clone(),
+                    region: region.to_string(),
+                    type_of_work: type_of_work.to_string(),
+                    contractor: project.contractor.clone(),
+                    metric: metric.name().to_string(),
+                    value: round2(value),
+                    group_median: round2(center),
+                    robust_z: round2(robust_z),
+                    lower_fence: round2(lower),
+                    upper_fence: round2(upper),
+                    method: method.to_string(),
+                });
+            }
+        }
+    }
+
+    rows.sort_by(|a, b| b.robust_z.abs().partial_cmp(&a.robust_z.abs()).unwrap());
+    (rows, fences)
+}
+
+/// Clamps savings and delay into their group's IQR fences. Savings are clamped by adjusting
+/// the contract cost and delay by moving the completion date, so the reports see the change.
+/// Savings % is only flagged, not clamped: it is derived from the same contract cost, and a
+/// second clamp could push savings back outside its own fences. Ongoing projects have no
+/// completion date to move and keep their delay.
+fn winsorise(project: &mut Project, definition: DelayDefinition, fences: &Fences) {
+    let key = |metric| (project.type_of_work.clone(), project.region.clone(), metric);
+
+    if let Some((lower, upper)) = fences.get(&key(Metric::Savings)) {
+        let savings = project.savings().clamp(*lower, *upper);
+        project.contract_cost = project.approved_budget - savings;
+    }
+
+    if let Some((lower, upper)) = fences.get(&key(Metric::DelayDays))
+        && let (Some(base), Some(delay)) = (
+            project.delay_base_date(definition),
+            project.delay_days(definition),
+        )
+    {
+        let clamped = (delay as f64).clamp(*lower, *upper).round() as i64;
+        project.actual_completion_date = Some(base + Duration::days(clamped));
+    }
+}
+
+/// `report1_regional_summary.csv` with suffix `excl_outliers` becomes
+/// `report1_regional_summary_excl_outliers.csv`.
+fn suffixed(file: &str, suffix: &str) -> String {
+    match file.rsplit_once('.') {
+        Some((stem, extension)) => format!("{stem}_{suffix}.{extension}"),
+        None => format!("{file}_{suffix}"),
+    }
+}
+
+fn show_rerun<T: Tabled + Serialize + Clone>(file: &str, rows: &[T], data: &DataSet) {
+    let table = Table::new(rows.to_vec());
+    println!("{table}");
+
+    export_csv(file, rows, data);
+
+    println!("Full table exported to {file}\n");
+}
+
+/// Reruns Reports 1-3 and the summary on the adjusted projects, exporting them next to the
+/// usual files with a suffix so the main outputs are left alone.
+fn recompute_reports(data: &DataSet, rows: &[OutlierReportRow], fences: &Fences, config: &Config) {
+    println!("Recompute Reports 1-3 and Summary");
+    println!("[1] With outliers excluded");
+    println!("[2] With outliers winsorised");
+    println!("[0] Skip");
+
+    print!("\nEnter Choice: ");
+    io::stdout().flush().unwrap();
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    println!();
+
+    let flagged: HashSet<usize> = rows.iter().map(|r| r.row_number).collect();
+    let mut adjusted = data.clone();
+
+    let suffix = match input.trim() {
+        "1" => {
+            adjusted
+                .projects
+                .retain(|p| !flagged.contains(&p.row_number));
+            println!(
+                "Excluded {} outlier projects\n",
+                data.projects.len() - adjusted.projects.len()
+            );
+            "excl_outliers"
+        }
+        "2" => {
+            for project in &mut adjusted.projects {
+                winsorise(project, data.delay.definition, fences);
+            }
+            println!("Savings, savings % and delay clamped to their group's IQR fences\n");
+            "winsorised"
+        }
+        _ => return,
+    };
+    adjusted.filtered_rows = adjusted.projects.len() as i32;
+
+    println!("Report 1: Regional Flood Mitigation Efficiency Summary");
+    let file = suffixed("report1_regional_summary.csv", suffix);
+    show_rerun(&file, &report1::report_rows(&adjusted, config), &adjusted);
+
+    println!("Report 2: Top Contractors Performance Ranking");
+    let mut contractors = report2::report_rows(&adjusted, config);
+    contractors.truncate(15);
+    let file = suffixed("report2_contractor_ranking.csv", suffix);
+    show_rerun(&file, &contractors, &adjusted);
+
+    println!("Report 3: Annual Project Type Cost Overrun Trends");
+    let file = suffixed("report3_project_type_trends.csv", suffix);
+    show_rerun(&file, &report3::report_rows(&adjusted, config), &adjusted);
+
+    let file = suffixed("summary.json", suffix);
+    println!("Summary Stats ({file})");
+    let stats = summary::summary(&adjusted);
+    println!("{}", serde_json::to_string_pretty(&stats).unwrap());
+    export_json(&file, &stats, &adjusted);
+    println!("Summary saved to {file}\n");
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+pub fn generate_report(data: &DataSet, config: &Config) {
+    println!("Generating Statistical Outlier Report...\n");
+
+    let outliers = &config.outliers;
+    let (rows, fences) = find_outliers(&data.projects, &data.delay, outliers);
+
+    let projects: HashSet<usize> = rows.iter().map(|r| r.row_number).collect();
+    println!(
+        "{} outlier values across {} projects (|robust z| > {} or beyond {} x IQR, within TypeOfWork and Region)\n",
+        rows.len(),
+        projects.len(),
+        outliers.z_threshold,
+        outliers.iqr_multiplier
+    );
+
+    let table = Table::new(rows.clone());
+    println!("{table}");
+
+    export_csv("report7_outliers.csv", &rows, data);
+
+    println!("Full table exported to report7_outliers.csv\n");
+
+    recompute_reports(data, &rows, &fences, config);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::project::fixtures::project;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn iqr_fences_flag_and_clamp_savings() {
+        let projects: Vec<Project> = [10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0, 100.0]
+            .into_iter()
+            .enumerate()
+            .map(|(row, savings)| {
+                let mut p = project(row, "Region A", "0");
+                p.approved_budget = 1000.0;
+                p.contract_cost = 1000.0 - savings;
+                p
+            })
+            .collect();
+        let options = DelayOptions {
+            definition: Default::default(),
+            policy: Default::default(),
+            reference_date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        };
+
+        let (rows, fences) = find_outliers(&projects, &options, &OutlierConfig::default());
+
+        // Q1 11.75 and Q3 15.25, so the fences are 1.5 IQRs (5.25) beyond them
+        let flagged: Vec<(usize, &str, f64, f64)> = rows
+            .iter()
+            .map(|r| {
+                (
+                    r.row_number,
+                    r.metric.as_str(),
+                    r.lower_fence,
+                    r.upper_fence,
+                )
+            })
+            .collect();
+        assert_eq!(
+            flagged,
+            [
+                (7, "Savings", 6.5, 20.5),
+                (7, "SavingsPctOfABC", 0.65, 2.05)
+            ]
+        );
+        assert!(rows.iter().all(|r| r.method == "Robust Z + IQR"));
+
+        let mut outlier = projects[7].clone();
+        winsorise(&mut outlier, options.definition, &fences);
+        assert_eq!(outlier.savings(), 20.5);
+        assert_eq!(
+            outlier.actual_completion_date,
+            projects[7].actual_completion_date
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use crate::services::export::export_csv;
+use crate::services::loader::DataSet;
+use crate::services::project::{Delay, DelayDefinition, DelayOptions, Project};
+use crate::services::stats::{quantile, sorted};
+use serde::Serialize;
+use std::collections::HashMap;
+use tabled::{Table, Tabled};
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct DelayDistributionRow {
+    pub scope: String,
+    pub group: String,
+    pub num_projects: usize,
+    pub p10: f64,
+    pub p25: f64,
+    pub p50: f64,
+    pub p75: f64,
+    pub p90: f64,
+    pub p99: f64,
+    /// On time under `schedule`; under `duration`, completed on or before the start date,
+    /// which points to bad dates.
+    pub days_0_or_less: usize,
+    pub days_1_30: usize,
+    pub days_31_90: usize,
+    pub days_91_180: usize,
+    pub days_181_365: usize,
+    pub over_365: usize,
+    pub incomplete_excluded: usize,
+    pub delay_missing: usize,
+    /// Ongoing projects whose delay so far is only a lower bound (`censored` policy); they are
+    /// not in the percentiles or buckets.
+    pub censored: usize,
+}
+
+/// Projects left out of the percentiles: excluded by policy, missing a delay, or censored.
+#[derive(Default)]
+struct Unmeasured {
+    excluded: usize,
+    missing: usize,
+    censored: usize,
+}
+
+// Contractors with fewer projects are left out, as in report2
+const MIN_CONTRACTOR_PROJECTS: usize = 5;
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn distribution_row(
+    scope: &str,
+    group: String,
+    delays: &[i64],
+    unmeasured: Unmeasured,
+) -> DelayDistributionRow {
+    let values: Vec<f64> = delays.iter().map(|d| *d as f64).collect();
+    let ordered = sorted(&values);
+    let p = |q: f64| round2(quantile(&ordered, q));
+    let bucket = |lo: i64, hi: i64| delays.iter().filter(|d| (lo..=hi).contains(*d)).count();
+
+    DelayDistributionRow {
+        scope: scope.to_string(),
+        group,
+        num_projects: delays.len(),
+        p10: p(0.10),
+        p25: p(0.25),
+        p50: p(0.50),
+        p75: p(0.75),
+        p90: p(0.90),
+        p99: p(0.99),
+        days_0_or_less: delays.iter().filter(|d| **d <= 0).count(),
+        days_1_30: bucket(1, 30),
+        days_31_90: bucket(31, 90),
+        days_91_180: bucket(91, 180),
+        days_181_365: bucket(181, 365),
+        over_365: delays.iter().filter(|d| **d > 365).count(),
+        incomplete_excluded: unmeasured.excluded,
+        delay_missing: unmeasured.missing,
+        censored: unmeasured.censored,
+    }
+}
+
+/// One row per group with at least `min_projects` measurable delays, by median delay descending.
+fn rows_by<F>(
+    projects: &[Project],
+    options: &DelayOptions,
+    scope: &str,
+    min_projects: usize,
+    key: F,
+) -> Vec<DelayDistributionRow>
+where
+    F: Fn(&Project) -> &str,
+{
+    let mut groups: HashMap<&str, (Vec<i64>, Unmeasured)> = HashMap::new();
+    for project in projects {
+        let (delays, unmeasured) = groups.entry(key(project)).or_default();
+        match project.delay(options) {
+            Delay::Observed(delay) => delays.push(delay),
+            Delay::Excluded => unmeasured.excluded += 1,
+            Delay::Missing => unmeasured.missing += 1,
+            Delay::Censored(_) => unmeasured.censored += 1,
+        }
+    }
+
+    let mut rows: Vec<DelayDistributionRow> = groups
+        .into_iter()
+        .filter(|(_, (delays, _))| !delays.is_empty() && delays.len() >= min_projects)
+        .map(|(group, (delays, unmeasured))| {
+            distribution_row(scope, group.to_string(), &delays, unmeasured)
+        })
+        .collect();
+
+    rows.sort_by(|a, b| b.p50.partial_cmp(&a.p50).unwrap());
+    rows
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+pub fn generate_report(data: &DataSet) {
+    println!("Generating Delay Distribution Report...\n");
+
+    let projects = &data.projects;
+    let options = &data.delay;
+    println!(
+        "Delay measured as {}, incomplete projects: {}\n",
+        options.definition.name(),
+        options.policy.name()
+    );
+
+    let mut rows: Vec<DelayDistributionRow> = Vec::new();
+    rows.extend(rows_by(projects, options, "National", 1, |_| "Philippines"));
+    rows.extend(rows_by(projects, options, "Region", 1, |p| &p.region));
+    rows.extend(rows_by(projects, options, "TypeOfWork", 1, |p| {
+        &p.type_of_work
+    }));
+    rows.extend(rows_by(
+        projects,
+        options,
+        "Contractor",
+        MIN_CONTRACTOR_PROJECTS,
+        |p| &p.contractor,
+    ));
+
+    if options.definition == DelayDefinition::Duration
+        && let Some(national) = rows.iter().find(|r| r.scope == "National")
+        && national.days_0_or_less > 0
+    {
+        println!(
+            "Warning: {} projects have a duration of 0 days or less; check their StartDate and ActualCompletionDate\n",
+            national.days_0_or_less
+        );
+    }
+
+    let table = Table::new(rows.clone());
+    println!("{table}");
+
+    export_csv("report8_delay_distribution.csv", &rows, data);
+
+    println!("Full table exported to report8_delay_distribution.csv\n");
+}
@@ -0,0 +1,189 @@
+use crate::services::export::export_csv;
+use crate::services::loader::DataSet;
+use crate::services::project::Project;
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use tabled::{Table, Tabled};
+
+// -----------------------------
+// Data Structures
+// -----------------------------
+/// One value per row (tidy long format) so the export can be charted directly.
+#[derive(Tabled, Debug, Clone, Serialize)]
+pub struct TimeSeriesRow {
+    pub period_type: String,
+    pub period: String,
+    pub group_by: String,
+    pub group: String,
+    pub metric: String,
+    pub value: f64,
+}
+
+/// Console view: one row per quarter and group with the metrics side by side.
+#[derive(Tabled, Debug, Clone)]
+struct QuarterSummaryRow {
+    period: String,
+    group: String,
+    starts: f64,
+    completions: f64,
+    total_abc: f64,
+    total_contract_cost: f64,
+    total_savings: f64,
+}
+
+#[derive(Default, Clone, Copy)]
+struct PeriodTotals {
+    starts: f64,
+    completions: f64,
+    total_abc: f64,
+    total_contract_cost: f64,
+    total_savings: f64,
+}
+
+#[derive(Clone, Copy)]
+enum Grouping {
+    None,
+    Region,
+    TypeOfWork,
+}
+
+impl Grouping {
+    fn name(self) -> &'static str {
+        match self {
+            Grouping::None => "None",
+            Grouping::Region => "Region",
+            Grouping::TypeOfWork => "TypeOfWork",
+        }
+    }
+
+    fn key(self, project: &Project) -> String {
+        match self {
+            Grouping::None => "All".to_string(),
+            Grouping::Region => project.region.clone(),
+            Grouping::TypeOfWork => project.type_of_work.clone(),
+        }
+    }
+}
+
+type Series = BTreeMap<(String, String), PeriodTotals>;
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn month(date: NaiveDate) -> String {
+    format!("{}-{:02}", date.year(), date.month())
+}
+
+fn quarter(date: NaiveDate) -> String {
+    format!("{}-Q{}", date.year(), (date.month() - 1) / 3 + 1)
+}
+
+fn ask_grouping() -> Grouping {
+    loop {
+        println!("Group time series by");
+        println!("[1] None");
+        println!("[2] Region");
+        println!("[3] TypeOfWork");
+
+        print!("\nEnter Choice: ");
+        io::stdout().flush().unwrap();
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        println!();
+
+        match input.trim() {
+            "1" => return Grouping::None,
+            "2" => return Grouping::Region,
+            "3" => return Grouping::TypeOfWork,
+            _ => println!("Invalid choice, try again.\n"),
+        }
+    }
+}
+
+/// Starts and amounts fall in the StartDate period; completions in the ActualCompletionDate period.
+fn aggregate(projects: &[Project], grouping: Grouping, period: fn(NaiveDate) -> String) -> Series {
+    let mut series = Series::new();
+
+    for project in projects {
+        let group = grouping.key(project);
+
+        if let Some(start) = project.start_date {
+            let totals = series.entry((period(start), group.clone())).or_default();
+            totals.starts += 1.0;
+            totals.total_abc += project.approved_budget;
+            totals.total_contract_cost += project.contract_cost;
+            totals.total_savings += project.savings();
+        }
+
+        if let Some(completed) = project.actual_completion_date {
+            series
+                .entry((period(completed), group))
+                .or_default()
+                .completions += 1.0;
+        }
+    }
+    series
+}
+
+fn long_rows(period_type: &str, grouping: Grouping, series: &Series) -> Vec<TimeSeriesRow> {
+    let mut rows = Vec::new();
+    for ((period, group), totals) in series {
+        let metrics = [
+            ("starts", totals.starts),
+            ("completions", totals.completions),
+            ("total_abc", totals.total_abc),
+            ("total_contract_cost", totals.total_contract_cost),
+            ("total_savings", totals.total_savings),
+        ];
+        for (metric, value) in metrics {
+            rows.push(TimeSeriesRow {
+                period_type: period_type.to_string(),
+                period: period.clone(),
+                group_by: grouping.name().to_string(),
+                group: group.clone(),
+                metric: metric.to_string(),
+                value: round2(value),
+            });
+        }
+    }
+    rows
+}
+
+// -----------------------------
+// Main Report Generator
+// -----------------------------
+pub fn generate_report(data: &DataSet) {
+    let grouping = ask_grouping();
+
+    println!("Generating Monthly and Quarterly Time Series...\n");
+
+    let monthly = aggregate(&data.projects, grouping, month);
+    let quarterly = aggregate(&data.projects, grouping, quarter);
+
+    let summary: Vec<QuarterSummaryRow> = quarterly
+        .iter()
+        .map(|((period, group), t)| QuarterSummaryRow {
+            period: period.clone(),
+            group: group.clone(),
+            starts: t.starts,
+            completions: t.completions,
+            total_abc: round2(t.total_abc),
+            total_contract_cost: round2(t.total_contract_cost),
+            total_savings: round2(t.total_savings),
+        })
+        .collect();
+    println!("{}", Table::new(summary));
+
+    let mut rows = long_rows("Month", grouping, &monthly);
+    rows.extend(long_rows("Quarter", grouping, &quarterly));
+
+    export_csv("report9_time_series.csv", &rows, data);
+
+    println!("Full monthly and quarterly series exported to report9_time_series.csv\n");
+}
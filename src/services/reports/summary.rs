@@ -1,90 +1,93 @@
+use crate::services::export::export_json;
 use crate::services::loader::DataSet;
+use crate::services::project::{Delay, ProjectStatus};
 use serde::Serialize;
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::Write;
 
 // -----------------------------
 // Data Structure
 // -----------------------------
 #[derive(Serialize)]
-struct Summary {
+pub struct Summary {
     total_projects: usize,
+    /// Distinct member firms, joint-venture partners included.
     total_contractors: usize,
+    contractor_names_as_written: usize,
     total_provinces: usize,
+    global_avg_duration: f64,
     global_avg_delay: f64,
     total_savings: f64,
+    completed_projects: usize,
+    ongoing_projects: usize,
+    unknown_status_projects: usize,
+    incomplete_excluded: usize,
+    delay_missing: usize,
+    /// Days past the target date; null when the file has no target dates.
+    global_avg_schedule_delay: Option<f64>,
 }
 
 // -----------------------------
 // Generate Summary
 // -----------------------------
-pub fn generate_summary(data: &DataSet) {
-    let headers = &data.headers;
-
-    let idx_contractor = headers.iter().position(|h| h == "Contractor").unwrap();
-    let idx_province = headers.iter().position(|h| h == "Province").unwrap();
-    let idx_budget = headers
-        .iter()
-        .position(|h| h == "ApprovedBudgetForContract")
-        .unwrap();
-    let idx_cost = headers.iter().position(|h| h == "ContractCost").unwrap();
-    let idx_start = headers.iter().position(|h| h == "StartDate").unwrap();
-    let idx_actual = headers
-        .iter()
-        .position(|h| h == "ActualCompletionDate")
-        .unwrap();
-
+pub fn summary(data: &DataSet) -> Summary {
     let mut contractors = HashSet::new();
+    let mut raw_contractors = HashSet::new();
     let mut provinces = HashSet::new();
-    let mut total_delay = 0.0;
+    let mut delays: Vec<Delay> = Vec::new();
+    let mut durations: Vec<Delay> = Vec::new();
+    let mut schedule_delays: Vec<Delay> = Vec::new();
+    let mut statuses = [0usize; 3];
+    let (mut incomplete_excluded, mut delay_missing) = (0, 0);
     let mut total_savings = 0.0;
 
-    for record in &data.matching_records {
-        let contractor = record.get(idx_contractor).unwrap_or("").to_string();
-        let province = record.get(idx_province).unwrap_or("").to_string();
-        let budget: f64 = record
-            .get(idx_budget)
-            .unwrap_or("0")
-            .replace(",", "")
-            .parse()
-            .unwrap_or(0.0);
-        let cost: f64 = record
-            .get(idx_cost)
-            .unwrap_or("0")
-            .replace(",", "")
-            .parse()
-            .unwrap_or(0.0);
-
-        contractors.insert(contractor);
-        provinces.insert(province);
-        total_savings += budget - cost;
+    for project in &data.projects {
+        contractors.extend(project.members.iter().map(|m| m.name.as_str()));
+        raw_contractors.insert(project.raw_contractor.as_str());
+        provinces.insert(project.province.as_str());
+        total_savings += project.savings();
 
         // Compute delay
-        let start =
-            chrono::NaiveDate::parse_from_str(record.get(idx_start).unwrap_or(""), "%Y-%m-%d").ok();
-        let actual =
-            chrono::NaiveDate::parse_from_str(record.get(idx_actual).unwrap_or(""), "%Y-%m-%d")
-                .ok();
-        if let (Some(s), Some(a)) = (start, actual) {
-            total_delay += (a - s).num_days() as f64;
+        let delay = project.delay(&data.delay);
+        match delay {
+            Delay::Excluded => incomplete_excluded += 1,
+            Delay::Missing => delay_missing += 1,
+            _ => {}
         }
+        delays.push(delay);
+        durations.push(project.duration(&data.delay));
+        schedule_delays.push(project.schedule_delay(&data.delay));
+
+        statuses[match project.status(data.delay.reference_date) {
+            ProjectStatus::Completed => 0,
+            ProjectStatus::Ongoing => 1,
+            ProjectStatus::Unknown => 2,
+        }] += 1;
     }
 
-    let total_projects = data.matching_records.len();
-    let global_avg_delay = if total_projects > 0 {
-        total_delay / total_projects as f64
-    } else {
-        0.0
-    };
+    let total_projects = data.projects.len();
+    // Average over the projects that have a measured delay, not all projects
+    let global_avg_delay = Delay::mean_observed(delays.iter()).unwrap_or(0.0);
+    let global_avg_duration = Delay::mean_observed(durations.iter()).unwrap_or(0.0);
 
-    let summary = Summary {
+    Summary {
         total_projects,
         total_contractors: contractors.len(),
+        contractor_names_as_written: raw_contractors.len(),
         total_provinces: provinces.len(),
+        global_avg_duration,
         global_avg_delay,
         total_savings,
-    };
+        completed_projects: statuses[0],
+        ongoing_projects: statuses[1],
+        unknown_status_projects: statuses[2],
+        incomplete_excluded,
+        delay_missing,
+        global_avg_schedule_delay: Delay::mean_observed(schedule_delays.iter()),
+    }
+}
+
+pub fn generate_summary(data: &DataSet) {
+    let summary = summary(data);
 
     // Convert to pretty JSON
     let json_str = serde_json::to_string_pretty(&summary).unwrap();
@@ -93,8 +96,7 @@ pub fn generate_summary(data: &DataSet) {
     println!("{json_str}");
 
     // Save to file
-    let mut file = File::create("summary.json").unwrap();
-    file.write_all(json_str.as_bytes()).unwrap();
+    export_json("summary.json", &summary, data);
 
     println!("Summary saved to summary.json");
 }
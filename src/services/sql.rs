@@ -0,0 +1,323 @@
+use crate::services::config::Config;
+use crate::services::export::write_metadata;
+use crate::services::loader::DataSet;
+use crate::services::project::Delay;
+use crate::services::reports::{report1, report2, report3, summary};
+use chrono::Local;
+use rusqlite::limits::Limit;
+use rusqlite::types::Value;
+use rusqlite::{Connection, params, params_from_iter};
+use serde::Serialize;
+use std::error::Error;
+
+// -----------------------------
+// Schema
+// -----------------------------
+/// One row per loaded project with the derived fields; dates are `YYYY-MM-DD` text.
+const PROJECTS_SCHEMA: &str = "
+CREATE TABLE projects (
+    row_number INTEGER PRIMARY KEY,
+    main_island TEXT,
+    region TEXT,
+    province TEXT,
+    municipality TEXT,
+    legislative_district TEXT,
+    project_id TEXT,
+    contract_id TEXT,
+    description TEXT,
+    contractor TEXT,
+    raw_contractor TEXT,
+    type_of_work TEXT,
+    funding_year INTEGER,
+    approved_budget REAL,
+    contract_cost REAL,
+    start_date TEXT,
+    actual_completion_date TEXT,
+    target_completion_date TEXT,
+    latitude REAL,
+    longitude REAL,
+    savings REAL,
+    delay_days INTEGER,
+    delay_at_least INTEGER,
+    status TEXT
+);
+CREATE TABLE project_members (
+    row_number INTEGER REFERENCES projects (row_number),
+    firm TEXT,
+    share REAL
+);";
+
+/// One row per export into the same file; the other tables hold the latest export only.
+const RUNS_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    run_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    generated_at TEXT,
+    source_file TEXT,
+    source_hash TEXT,
+    source_rows INTEGER,
+    projects INTEGER,
+    year_filter TEXT,
+    filter TEXT,
+    bbox TEXT,
+    delay_definition TEXT,
+    incomplete_policy TEXT,
+    reference_date TEXT,
+    efficiency_formula TEXT,
+    jv_attribution TEXT,
+    bootstrap_iterations INTEGER,
+    reference_tables TEXT
+);";
+
+// Replaced on every export; project_members goes before the projects it references
+const RESULT_TABLES: [&str; 6] = [
+    "project_members",
+    "projects",
+    "regional_efficiency",
+    "contractor_ranking",
+    "project_type_trends",
+    "summary",
+];
+
+// -----------------------------
+// Utility Functions
+// -----------------------------
+fn sql_value(value: serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Integer(i64::from(b)),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Real(n.as_f64().unwrap()),
+        },
+        serde_json::Value::String(s) => Value::Text(s),
+        other => Value::Text(other.to_string()),
+    }
+}
+
+fn sql_type(value: &Value) -> &'static str {
+    match value {
+        Value::Integer(_) => "INTEGER",
+        Value::Real(_) => "REAL",
+        _ => "TEXT",
+    }
+}
+
+// -----------------------------
+// Writers
+// -----------------------------
+/// Creates table `name` from serialisable rows, one column per field in declaration order.
+/// Each column takes the type of its first non-null value, or REAL when it has none (the
+/// optional report fields are all numbers); no table is made for no rows.
+fn write_rows<T: Serialize>(
+    conn: &Connection,
+    name: &str,
+    rows: &[T],
+) -> Result<(), Box<dyn Error>> {
+    let mut records: Vec<Vec<(String, Value)>> = Vec::new();
+    for row in rows {
+        let serde_json::Value::Object(fields) = serde_json::to_value(row)? else {
+            return Err(format!("rows for {name} are not structs").into());
+        };
+        records.push(fields.into_iter().map(|(k, v)| (k, sql_value(v))).collect());
+    }
+    let Some(first) = records.first() else {
+        return Ok(());
+    };
+
+    let columns: Vec<String> = first
+        .iter()
+        .enumerate()
+        .map(|(i, (column, _))| {
+            let kind = records
+                .iter()
+                .map(|r| &r[i].1)
+                .find(|v| **v != Value::Null)
+                .map_or("REAL", sql_type);
+            format!("\"{column}\" {kind}")
+        })
+        .collect();
+    conn.execute_batch(&format!(
+        "CREATE TABLE \"{name}\" ({});",
+        columns.join(", ")
+    ))?;
+
+    let placeholders = vec!["?"; columns.len()].join(", ");
+    let mut insert = conn.prepare(&format!("INSERT INTO \"{name}\" VALUES ({placeholders})"))?;
+    for record in records {
+        insert.execute(params_from_iter(record.into_iter().map(|(_, v)| v)))?;
+    }
+    Ok(())
+}
+
+/// Creates `projects` and `project_members` in `conn` and fills them from `data`; callers
+/// wrap it in a transaction.
+pub fn write_projects(conn: &Connection, data: &DataSet) -> rusqlite::Result<()> {
+    conn.execute_batch(PROJECTS_SCHEMA)?;
+
+    let mut insert = conn.prepare(
+        "INSERT INTO projects VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
+         ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24)",
+    )?;
+    let mut insert_member = conn.prepare("INSERT INTO project_members VALUES (?1, ?2, ?3)")?;
+
+    for p in &data.projects {
+        let delay = p.delay(&data.delay);
+        let at_least = match delay {
+            Delay::Censored(days) => Some(days),
+            _ => None,
+        };
+        insert.execute(params![
+            p.row_number,
+            p.main_island,
+            p.region,
+            p.province,
+            p.municipality,
+            p.legislative_district,
+            p.project_id,
+            p.contract_id,
+            p.description,
+            p.contractor,
+            p.raw_contractor,
+            p.type_of_work,
+            p.funding_year.trim().parse::<i64>().ok(),
+            p.approved_budget,
+            p.contract_cost,
+            p.start_date.map(|d| d.to_string()),
+            p.actual_completion_date.map(|d| d.to_string()),
+            p.target_completion_date.map(|d| d.to_string()),
+            p.coordinates.map(|c| c.latitude),
+            p.coordinates.map(|c| c.longitude),
+            p.savings(),
+            delay.observed(),
+            at_least,
+            p.status(data.delay.reference_date).name(),
+        ])?;
+        for member in &p.members {
+            insert_member.execute(params![p.row_number, member.name, member.share])?;
+        }
+    }
+    Ok(())
+}
+
+// -----------------------------
+// Queries
+// -----------------------------
+/// In-memory database holding the loaded projects.
+pub fn open_projects(data: &DataSet) -> rusqlite::Result<Connection> {
+    let mut conn = Connection::open_in_memory()?;
+    let tx = conn.transaction()?;
+    write_projects(&tx, data)?;
+    tx.commit()?;
+
+    // Queries may only read the projects: no writes, and no other database files attached
+    conn.pragma_update(None, "query_only", true)?;
+    conn.set_limit(Limit::SQLITE_LIMIT_ATTACHED, 0);
+    Ok(conn)
+}
+
+fn value_text(value: &Value, round: bool) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Integer(v) => v.to_string(),
+        Value::Real(v) if round => ((v * 100.0).round() / 100.0).to_string(),
+        Value::Real(v) => v.to_string(),
+        Value::Text(v) => v.clone(),
+        Value::Blob(v) => format!("<{} bytes>", v.len()),
+    }
+}
+
+/// Text for the printed table, with reals to 2 decimals.
+pub fn display_value(value: &Value) -> String {
+    value_text(value, true)
+}
+
+/// Text for the exported CSV, with reals at full precision (coordinates, JV shares).
+pub fn export_value(value: &Value) -> String {
+    value_text(value, false)
+}
+
+/// Runs one read-only statement and returns its column names and rows.
+pub fn run_query(conn: &Connection, sql: &str) -> rusqlite::Result<(Vec<String>, Vec<Vec<Value>>)> {
+    let mut statement = conn.prepare(sql)?;
+    if !statement.readonly() {
+        return Err(rusqlite::Error::InvalidQuery);
+    }
+    let headers: Vec<String> = statement
+        .column_names()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    let mut rows = Vec::new();
+    let mut results = statement.query([])?;
+    while let Some(row) = results.next()? {
+        let values = (0..headers.len())
+            .map(|i| row.get::<_, Value>(i))
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        rows.push(values);
+    }
+    Ok((headers, rows))
+}
+
+// -----------------------------
+// Export
+// -----------------------------
+/// Writes the projects, the Report 1-3 tables and the summary to the SQLite file at `path`,
+/// replacing them if the file already has them, and appends a row to `runs`.
+pub fn export_database(path: &str, data: &DataSet, config: &Config) -> Result<(), Box<dyn Error>> {
+    let mut conn = Connection::open(path)?;
+    let tx = conn.transaction()?;
+
+    for table in RESULT_TABLES {
+        tx.execute_batch(&format!("DROP TABLE IF EXISTS {table};"))?;
+    }
+    write_projects(&tx, data)?;
+    write_rows(
+        &tx,
+        "regional_efficiency",
+        &report1::report_rows(data, config),
+    )?;
+    write_rows(
+        &tx,
+        "contractor_ranking",
+        &report2::report_rows(data, config),
+    )?;
+    write_rows(
+        &tx,
+        "project_type_trends",
+        &report3::report_rows(data, config),
+    )?;
+    write_rows(&tx, "summary", &[summary::summary(data)])?;
+
+    tx.execute_batch(RUNS_SCHEMA)?;
+    tx.execute(
+        "INSERT INTO runs (generated_at, source_file, source_hash, source_rows, projects,
+         year_filter, filter, bbox, delay_definition, incomplete_policy, reference_date,
+         efficiency_formula, jv_attribution, bootstrap_iterations, reference_tables)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        params![
+            Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            data.source_file,
+            data.source_hash,
+            data.total_rows,
+            data.projects.len(),
+            data.year_filter,
+            data.filter,
+            data.bbox.map(|b| b.describe()),
+            data.delay.definition.name(),
+            data.delay.policy.name(),
+            data.delay.reference_date.to_string(),
+            config.efficiency.formula.name(),
+            config.contractors.jv_attribution.name(),
+            config
+                .bootstrap
+                .enabled
+                .then_some(config.bootstrap.iterations),
+            data.reference.describe().join("; "),
+        ],
+    )?;
+    tx.commit()?;
+
+    write_metadata(path, data.projects.len(), data);
+    Ok(())
+}
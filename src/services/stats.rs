@@ -0,0 +1,135 @@
+use std::cmp::Ordering;
+
+// -----------------------------
+// Descriptive Statistics
+// -----------------------------
+pub fn sorted(values: &[f64]) -> Vec<f64> {
+    let mut values = values.to_vec();
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    values
+}
+
+/// Linearly interpolated quantile (`q` in 0-1) of an already sorted slice.
+pub fn quantile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let pos = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+}
+
+pub fn median(values: &[f64]) -> f64 {
+    quantile(&sorted(values), 0.5)
+}
+
+/// Median absolute deviation from the median.
+pub fn mad(values: &[f64]) -> f64 {
+    let center = median(values);
+    let deviations: Vec<f64> = values.iter().map(|v| (v - center).abs()).collect();
+    median(&deviations)
+}
+
+// -----------------------------
+// Standardisation
+// -----------------------------
+/// Population z-scores; all zero when the values do not vary.
+pub fn z_scores(values: &[f64]) -> Vec<f64> {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let sd = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+    values
+        .iter()
+        .map(|v| if sd > 0.0 { (v - mean) / sd } else { 0.0 })
+        .collect()
+}
+
+/// 1-based ranks in ascending order; ties share their average rank.
+pub fn ranks(values: &[f64]) -> Vec<f64> {
+    values
+        .iter()
+        .map(|v| {
+            let below = values.iter().filter(|o| o.total_cmp(v).is_lt()).count();
+            let equal = values.iter().filter(|o| o.total_cmp(v).is_eq()).count();
+            below as f64 + (equal + 1) as f64 / 2.0
+        })
+        .collect()
+}
+
+/// Percentile rank (0-100, ties share their average rank) of each value among all of them.
+pub fn percentile_ranks(values: &[f64]) -> Vec<f64> {
+    if values.len() < 2 {
+        return vec![100.0; values.len()];
+    }
+    let last = (values.len() - 1) as f64;
+    ranks(values)
+        .iter()
+        .map(|rank| (rank - 1.0) / last * 100.0)
+        .collect()
+}
+
+// -----------------------------
+// Rank Correlation
+// -----------------------------
+/// Kendall's tau-b between two paired samples (1 when they order the items identically).
+pub fn kendall_tau(a: &[f64], b: &[f64]) -> f64 {
+    let (mut concordant, mut discordant) = (0.0_f64, 0.0_f64);
+    let (mut ties_a, mut ties_b) = (0.0_f64, 0.0_f64);
+
+    for i in 0..a.len() {
+        for j in (i + 1)..a.len() {
+            let da = a[i].total_cmp(&a[j]);
+            let db = b[i].total_cmp(&b[j]);
+            match (da, db) {
+                (Ordering::Equal, Ordering::Equal) => {}
+                (Ordering::Equal, _) => ties_a += 1.0,
+                (_, Ordering::Equal) => ties_b += 1.0,
+                _ if da == db => concordant += 1.0,
+                _ => discordant += 1.0,
+            }
+        }
+    }
+
+    let denominator =
+        ((concordant + discordant + ties_a) * (concordant + discordant + ties_b)).sqrt();
+    if denominator == 0.0 {
+        1.0
+    } else {
+        (concordant - discordant) / denominator
+    }
+}
+
+/// Spearman's rho: the Pearson correlation of the average ranks.
+pub fn spearman(a: &[f64], b: &[f64]) -> f64 {
+    let (ra, rb) = (ranks(a), ranks(b));
+    let n = ra.len() as f64;
+    let (ma, mb) = (ra.iter().sum::<f64>() / n, rb.iter().sum::<f64>() / n);
+    let cov: f64 = ra.iter().zip(&rb).map(|(x, y)| (x - ma) * (y - mb)).sum();
+    let va: f64 = ra.iter().map(|x| (x - ma).powi(2)).sum();
+    let vb: f64 = rb.iter().map(|y| (y - mb).powi(2)).sum();
+    if va == 0.0 || vb == 0.0 {
+        1.0
+    } else {
+        cov / (va * vb).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kendall_tau_counts_discordant_pairs() {
+        // One discordant pair out of six: (5 - 1) / 6
+        let tau = kendall_tau(&[1.0, 2.0, 3.0, 4.0], &[1.0, 3.0, 2.0, 4.0]);
+        assert!((tau - 2.0 / 3.0).abs() < 1e-12, "{tau}");
+        assert_eq!(kendall_tau(&[1.0, 2.0, 3.0], &[3.0, 2.0, 1.0]), -1.0);
+    }
+
+    #[test]
+    fn kendall_tau_corrects_for_ties() {
+        // Two concordant pairs and one tied in `a` only: 2 / sqrt(3 * 2)
+        let tau = kendall_tau(&[1.0, 1.0, 2.0], &[1.0, 2.0, 3.0]);
+        assert!((tau - 2.0 / 6.0_f64.sqrt()).abs() < 1e-12, "{tau}");
+    }
+}